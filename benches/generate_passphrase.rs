@@ -1,5 +1,5 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use phraze::cli::ListChoice;
+use phraze::list_choice::ListChoice;
 use phraze::*;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -20,8 +20,10 @@ fn criterion_benchmark(c: &mut Criterion) {
                 number_of_words_to_put_in_passphrase,
                 separator,
                 title_case,
+                false,
                 wordlist,
             )
+            .unwrap()
         })
     });
 }
@@ -1,17 +1,76 @@
+#[cfg(feature = "cli")]
+#[path = "src/alphabets.rs"]
+#[allow(dead_code)]
+mod alphabets;
+#[cfg(feature = "cli")]
+#[path = "src/casing.rs"]
+#[allow(dead_code)]
+mod casing;
+#[cfg(feature = "cli")]
+#[path = "src/charset.rs"]
+#[allow(dead_code)]
+mod charset;
+#[cfg(feature = "cli")]
 #[path = "src/cli.rs"]
+#[allow(dead_code)]
 mod cli;
+#[cfg(feature = "cli")]
+#[path = "src/keyboard_layouts.rs"]
+#[allow(dead_code)]
+mod keyboard_layouts;
+#[path = "src/list_checks.rs"]
+mod list_checks;
+#[path = "src/list_choice.rs"]
+#[allow(dead_code)]
+mod list_choice;
+#[cfg(feature = "cli")]
+#[path = "src/presets.rs"]
+#[allow(dead_code)]
+mod presets;
+#[cfg(feature = "cli")]
+#[path = "src/pseudo_words.rs"]
+#[allow(dead_code)]
+mod pseudo_words;
+#[cfg(feature = "cli")]
+#[path = "src/templates.rs"]
+#[allow(dead_code)]
+mod templates;
+#[path = "src/unicode_normalization_check.rs"]
+#[allow(dead_code)]
+mod unicode_normalization_check;
 
+#[cfg(feature = "cli")]
 use clap::Command;
-use clap::CommandFactory;
+#[cfg(feature = "cli")]
 use clap_complete::Shell::{Bash, Fish, Zsh};
+#[cfg(feature = "cli")]
 use clap_complete::generate_to;
+#[cfg(feature = "cli")]
 use clap_mangen::Man;
+#[cfg(feature = "cli")]
 use cli::Args;
+use list_checks::problems_with_list;
+#[cfg(feature = "cli")]
+use list_choice::ListChoice;
+use list_choice::ListMetadata;
 use std::fs;
 use std::path::PathBuf;
 
+/// How many words each list in word-lists/ should have
+const EXPECTED_WORD_COUNTS: &[(&str, usize)] = &[
+    ("eff-long.txt", 7776),
+    ("eff-short-1.txt", 1296),
+    ("mnemonicode.txt", 1633),
+    ("orchard-street-alpha.txt", 1296),
+    ("orchard-street-long.txt", 17576),
+    ("orchard-street-medium.txt", 8192),
+    ("orchard-street-qwerty.txt", 1296),
+];
+
+#[cfg(feature = "cli")]
 static NAME: &str = "phraze";
 
+#[cfg(feature = "cli")]
 fn generate_man_pages(cmd: Command) {
     let man_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/man");
     let mut buffer = Vec::default();
@@ -21,6 +80,7 @@ fn generate_man_pages(cmd: Command) {
     fs::write(man_dir.join(NAME.to_owned() + ".1"), buffer).unwrap();
 }
 
+#[cfg(feature = "cli")]
 fn generate_shell_completions(mut cmd: Command) {
     let comp_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/completions");
 
@@ -31,10 +91,86 @@ fn generate_shell_completions(mut cmd: Command) {
     }
 }
 
+/// Check every list in word-lists/ for the properties Phraze promises (see the `list_checks`
+/// module), panicking, and so failing the build, if any list falls short. Returns each list's file
+/// name and metadata.
+fn check_word_lists() -> Vec<(String, ListMetadata)> {
+    let lists_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("word-lists");
+    let mut file_names: Vec<String> = fs::read_dir(&lists_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|file_name| file_name.ends_with(".txt"))
+        .collect();
+    file_names.sort();
+
+    let mut all_problems = vec![];
+    let mut metadata = vec![];
+    for file_name in file_names {
+        let contents = fs::read_to_string(lists_dir.join(&file_name)).unwrap();
+        let list: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+        let mut problems = problems_with_list(&list);
+        match EXPECTED_WORD_COUNTS
+            .iter()
+            .find(|(name, _)| *name == file_name)
+        {
+            Some((_, expected)) if *expected != list.len() => problems.push(format!(
+                "has {} words, but should have {}",
+                list.len(),
+                expected
+            )),
+            Some(_) => {}
+            None => problems.push("has no expected word count in build.rs".to_string()),
+        }
+        for problem in problems {
+            all_problems.push(format!("word-lists/{} {}", file_name, problem));
+        }
+        metadata.push((
+            file_name,
+            ListMetadata {
+                word_count: list.len(),
+                bits_per_word: (list.len() as f64).log2(),
+            },
+        ));
+    }
+    if !all_problems.is_empty() {
+        panic!("Word lists failed checks:\n{}", all_problems.join("\n"));
+    }
+    metadata
+}
+
+/// Write the lists' metadata as Rust source, to be included in the library
+fn write_list_metadata(metadata: &[(String, ListMetadata)]) {
+    let mut source = "pub const BUNDLED_LIST_METADATA: &[(&str, ListMetadata)] = &[\n".to_string();
+    for (file_name, list_metadata) in metadata {
+        source += &format!(
+            "    ({:?}, ListMetadata {{ word_count: {}, bits_per_word: {:?} }}),\n",
+            file_name, list_metadata.word_count, list_metadata.bits_per_word
+        );
+    }
+    source += "];\n";
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("list_metadata.rs"), source).unwrap();
+}
+
 fn main() {
-    let mut cmd = Args::command();
-    cmd.set_bin_name(NAME);
+    let metadata = check_word_lists();
+    write_list_metadata(&metadata);
 
-    generate_man_pages(cmd.clone());
-    generate_shell_completions(cmd);
+    // Man pages and shell completions are generated from the command-line interface, so they're
+    // skipped when Phraze is built as a library without the `cli` feature
+    #[cfg(feature = "cli")]
+    {
+        let list_metadata = |list_choice: ListChoice| {
+            metadata
+                .iter()
+                .find(|(file_name, _)| file_name == list_choice.file_name())
+                .map(|(_, list_metadata)| *list_metadata)
+                .unwrap()
+        };
+        let mut cmd = Args::command_with_list_metadata(&list_metadata);
+        cmd.set_bin_name(NAME);
+
+        generate_man_pages(cmd.clone());
+        generate_shell_completions(cmd);
+    }
 }
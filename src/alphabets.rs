@@ -0,0 +1,98 @@
+//! Sometimes a system demands a random password of characters, rather than a passphrase. With
+//! `--chars`, Phraze treats an alphabet as a word list of one-character "words", so passwords get
+//! the same entropy math, strength options and checks as passphrases.
+use std::fmt;
+use std::str::FromStr;
+
+/// Characters a random password can be made from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Alphabet {
+    /// Lowercase ASCII letters
+    Lowercase,
+    /// ASCII letters and digits
+    Alnum,
+    /// Every printable ASCII character except space
+    Printable,
+    /// ASCII letters, digits and punctuation that never needs quoting in a Unix shell
+    ShellSafe,
+    /// Exactly the characters the user gave
+    Custom(Vec<char>),
+}
+
+/// Punctuation a POSIX shell treats as part of a word, so a password with it can be pasted into
+/// a command line without quotes
+const SHELL_SAFE_PUNCTUATION: &[char] = &['%', '+', ',', '-', '.', '/', ':', '=', '@', '_'];
+
+impl Alphabet {
+    /// Every character of this alphabet, sorted and without duplicates
+    pub fn chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = match self {
+            Alphabet::Lowercase => ('a'..='z').collect(),
+            Alphabet::Alnum => ('a'..='z').chain('A'..='Z').chain('0'..='9').collect(),
+            Alphabet::Printable => ('!'..='~').collect(),
+            Alphabet::ShellSafe => ('a'..='z')
+                .chain('A'..='Z')
+                .chain('0'..='9')
+                .chain(SHELL_SAFE_PUNCTUATION.iter().copied())
+                .collect(),
+            Alphabet::Custom(chars) => chars.clone(),
+        };
+        chars.sort_unstable();
+        chars.dedup();
+        chars
+    }
+}
+
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Alphabet::Lowercase => write!(f, "lowercase"),
+            Alphabet::Alnum => write!(f, "alnum"),
+            Alphabet::Printable => write!(f, "printable"),
+            Alphabet::ShellSafe => write!(f, "shell-safe"),
+            Alphabet::Custom(chars) => write!(f, "custom:{}", chars.iter().collect::<String>()),
+        }
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Custom characters are case-sensitive, so only lowercase the name
+        if let Some(chars) = s.strip_prefix("custom:") {
+            if chars.is_empty() {
+                return Err(
+                    "Custom alphabet needs at least one character, like custom:abc".to_string(),
+                );
+            }
+            return Ok(Alphabet::Custom(chars.chars().collect()));
+        }
+        match s.to_lowercase().as_ref() {
+            "lowercase" | "lower" => Ok(Alphabet::Lowercase),
+            "alnum" => Ok(Alphabet::Alnum),
+            "printable" => Ok(Alphabet::Printable),
+            "shell-safe" | "shellsafe" => Ok(Alphabet::ShellSafe),
+            _ => Err(format!(
+                "Inputted alphabet '{}' isn't one Phraze knows about. Options are lowercase, alnum, printable, shell-safe and custom: followed by the characters to use",
+                s
+            )),
+        }
+    }
+}
+
+#[test]
+fn can_list_the_characters_of_an_alphabet() {
+    assert_eq!(Alphabet::Lowercase.chars().len(), 26);
+    assert_eq!(Alphabet::Alnum.chars().len(), 62);
+    assert_eq!(Alphabet::Printable.chars().len(), 94);
+    assert_eq!(Alphabet::ShellSafe.chars().len(), 72);
+    assert!(!Alphabet::ShellSafe.chars().contains(&'$'));
+    assert_eq!(
+        "custom:abca".parse::<Alphabet>().unwrap().chars(),
+        vec!['a', 'b', 'c']
+    );
+    assert_eq!("shell-safe".parse(), Ok(Alphabet::ShellSafe));
+    assert!("custom:".parse::<Alphabet>().is_err());
+    assert!("emoji".parse::<Alphabet>().is_err());
+}
@@ -0,0 +1,163 @@
+//! Title casing words from the word list. Making the first `char` of a word uppercase works fine
+//! for plain ASCII words, but not for every word a custom list might have: a word in NFD might
+//! start with a letter followed by a combining accent, a ligature like "ﬁ" uppercases to two
+//! letters, and in Turkish the capital of "i" is "İ", not "I". So we work on the word's first
+//! extended grapheme cluster (what a reader would call its first letter), apply any rules for
+//! the user's locale, and then put the word back in the normalization form the list uses.
+use crate::unicode_normalization_check::NormalizationForm;
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Languages with casing rules that differ from Unicode's defaults
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Locale {
+    /// Dotted and dotless i are separate letters: i and İ, ı and I
+    Turkish,
+    /// Same dotted and dotless i as Turkish
+    Azerbaijani,
+    /// The digraph "ij" is capitalized as a unit at the start of a word: "IJsbeer"
+    Dutch,
+}
+
+impl Locale {
+    fn uses_dotted_and_dotless_i(self) -> bool {
+        matches!(self, Locale::Turkish | Locale::Azerbaijani)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Locale::Turkish => write!(f, "tr"),
+            Locale::Azerbaijani => write!(f, "az"),
+            Locale::Dutch => write!(f, "nl"),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts a language code, like "tr", or a longer tag that starts with one, like "tr-TR"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        match language.as_ref() {
+            "tr" => Ok(Locale::Turkish),
+            "az" => Ok(Locale::Azerbaijani),
+            "nl" => Ok(Locale::Dutch),
+            _ => Err(format!(
+                "Phraze doesn't have special casing rules for locale '{}'. Locales with special rules are tr, az and nl. Other languages don't need --locale",
+                s
+            )),
+        }
+    }
+}
+
+/// Make given word all lowercase, then make its first letter a capital. If `form` is given, the
+/// result is put in that normalization form.
+pub fn title_case(word: &str, locale: Option<Locale>, form: Option<NormalizationForm>) -> String {
+    let lowercase = to_lowercase(word, locale);
+    let title_cased = if locale == Some(Locale::Dutch) && lowercase.starts_with("ij") {
+        "IJ".to_string() + &lowercase["ij".len()..]
+    } else {
+        let mut graphemes = lowercase.graphemes(true);
+        match graphemes.next() {
+            None => String::new(),
+            // Any combining marks in the first grapheme stay as they are, after the capital
+            Some(first_grapheme) => {
+                let mut chars = first_grapheme.chars();
+                let first_char = chars.next().expect("Graphemes aren't empty");
+                title_case_char(first_char, locale) + chars.as_str() + graphemes.as_str()
+            }
+        }
+    };
+    match form {
+        Some(form) => form.normalize(&title_cased),
+        None => title_cased,
+    }
+}
+
+fn to_lowercase(word: &str, locale: Option<Locale>) -> String {
+    match locale {
+        Some(locale) if locale.uses_dotted_and_dotless_i() => word
+            // A capital I followed by a combining dot above is the NFD spelling of İ
+            .replace("I\u{307}", "i")
+            .chars()
+            .map(|c| match c {
+                'I' => "ı".to_string(),
+                'İ' => "i".to_string(),
+                _ => c.to_lowercase().collect(),
+            })
+            .collect(),
+        _ => word.to_lowercase(),
+    }
+}
+
+/// The title case form of a single character. Usually that's just its uppercase form, but not
+/// always.
+fn title_case_char(c: char, locale: Option<Locale>) -> String {
+    if c == 'i' && locale.is_some_and(Locale::uses_dotted_and_dotless_i) {
+        return "İ".to_string();
+    }
+    // Digraphs with a separate title case form, in between their lowercase and uppercase forms
+    match c {
+        'ǆ' | 'ǅ' | 'Ǆ' => return "ǅ".to_string(),
+        'ǉ' | 'ǈ' | 'Ǉ' => return "ǈ".to_string(),
+        'ǌ' | 'ǋ' | 'Ǌ' => return "ǋ".to_string(),
+        'ǳ' | 'ǲ' | 'Ǳ' => return "ǲ".to_string(),
+        _ => {}
+    }
+    // Some characters, like ß and the ligature ﬁ, uppercase to more than one letter ("SS" and
+    // "FI"). In title case, only the first of those letters should be a capital: "Ss" and "Fi".
+    let uppercase: String = c.to_uppercase().collect();
+    let mut uppercase_chars = uppercase.chars();
+    match uppercase_chars.next() {
+        Some(first) => first.to_string() + &uppercase_chars.as_str().to_lowercase(),
+        None => String::new(),
+    }
+}
+
+#[test]
+fn can_title_case_words_that_start_with_a_combining_mark() {
+    // "école" in NFD: e followed by a combining acute accent
+    let nfd_word = "e\u{301}cole";
+    assert_eq!(
+        title_case(nfd_word, None, Some(NormalizationForm::Nfd)),
+        "E\u{301}cole"
+    );
+    assert_eq!(
+        title_case(nfd_word, None, Some(NormalizationForm::Nfc)),
+        "École"
+    );
+    // ǰ has no precomposed capital, so uppercasing it in NFC gives J plus a combining caron
+    assert_eq!(
+        title_case("ǰob", None, Some(NormalizationForm::Nfc)),
+        "J\u{30c}ob"
+    );
+}
+
+#[test]
+fn can_title_case_ligatures_and_digraphs() {
+    assert_eq!(title_case("ﬁsh", None, None), "Fish");
+    assert_eq!(title_case("ßa", None, None), "Ssa");
+    assert_eq!(title_case("ǆungla", None, None), "ǅungla");
+    assert_eq!(title_case("ALPHA", None, None), "Alpha");
+}
+
+#[test]
+fn can_title_case_with_locale_rules() {
+    assert_eq!(title_case("istanbul", None, None), "Istanbul");
+    assert_eq!(
+        title_case("istanbul", Some(Locale::Turkish), None),
+        "İstanbul"
+    );
+    // Dotless capital I lowercases to dotless ı, and dotted İ to dotted i
+    assert_eq!(title_case("IRMAK", Some(Locale::Turkish), None), "Irmak");
+    assert_eq!(title_case("KIŞ", Some(Locale::Turkish), None), "Kış");
+    assert_eq!(title_case("İZMİR", Some(Locale::Turkish), None), "İzmir");
+    assert_eq!(title_case("ijsbeer", Some(Locale::Dutch), None), "IJsbeer");
+    assert_eq!(title_case("ijsbeer", None, None), "Ijsbeer");
+    assert_eq!("tr-TR".parse::<Locale>(), Ok(Locale::Turkish));
+    assert!("en".parse::<Locale>().is_err());
+}
@@ -0,0 +1,94 @@
+//! Some systems, like BIOS prompts and older routers, only accept certain characters in a
+//! password. A `Charset` is the set of characters a passphrase is allowed to use. Phraze leaves
+//! out any words that can't be written in it, and only picks random separators from it.
+use std::fmt;
+use std::str::FromStr;
+
+/// A set of characters generated passphrases must stick to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Charset {
+    /// Printable ASCII: letters, digits, punctuation and space
+    Ascii,
+    /// ASCII letters and digits only
+    Alnum,
+    /// Printable characters of ISO 8859-1 (Latin-1), which adds accented letters used in many
+    /// Western European languages to printable ASCII
+    Latin1,
+    /// Exactly the characters the user gave
+    Custom(Vec<char>),
+}
+
+impl Charset {
+    /// Returns true if the given character is in this charset
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            Charset::Ascii => (' '..='~').contains(&c),
+            Charset::Alnum => c.is_ascii_alphanumeric(),
+            Charset::Latin1 => (' '..='~').contains(&c) || ('\u{a0}'..='\u{ff}').contains(&c),
+            Charset::Custom(chars) => chars.contains(&c),
+        }
+    }
+
+    /// Returns true if every character of the given word is in this charset
+    pub fn can_write(&self, word: &str) -> bool {
+        word.chars().all(|c| self.contains(c))
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Charset::Ascii => write!(f, "ascii"),
+            Charset::Alnum => write!(f, "alnum"),
+            Charset::Latin1 => write!(f, "latin1"),
+            Charset::Custom(chars) => write!(f, "custom:{}", chars.iter().collect::<String>()),
+        }
+    }
+}
+
+impl FromStr for Charset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Custom characters are case-sensitive, so only lowercase the name
+        if let Some(chars) = s.strip_prefix("custom:") {
+            if chars.is_empty() {
+                return Err(
+                    "Custom charset needs at least one character, like custom:abc".to_string(),
+                );
+            }
+            return Ok(Charset::Custom(chars.chars().collect()));
+        }
+        match s.to_lowercase().as_ref() {
+            "ascii" => Ok(Charset::Ascii),
+            "alnum" => Ok(Charset::Alnum),
+            "latin1" | "latin-1" => Ok(Charset::Latin1),
+            _ => Err(format!(
+                "Inputted charset '{}' isn't one Phraze knows about. Options are ascii, alnum, latin1 and custom: followed by the characters to allow",
+                s
+            )),
+        }
+    }
+}
+
+#[test]
+fn can_tell_which_words_a_charset_can_write() {
+    assert!(Charset::Ascii.can_write("hello world!"));
+    assert!(!Charset::Ascii.can_write("café"));
+    assert!(!Charset::Alnum.can_write("well-known"));
+    assert!(Charset::Alnum.can_write("Route66"));
+    assert!(Charset::Latin1.can_write("café"));
+    assert!(!Charset::Latin1.can_write("łódź"));
+    let custom: Charset = "custom:abc-".parse().unwrap();
+    assert!(custom.can_write("cab-a"));
+    assert!(!custom.can_write("Cab"));
+}
+
+#[test]
+fn can_parse_and_display_charsets() {
+    for charset in ["ascii", "alnum", "latin1", "custom:xyz!"] {
+        assert_eq!(charset.parse::<Charset>().unwrap().to_string(), charset);
+    }
+    assert!("custom:".parse::<Charset>().is_err());
+    assert!("ebcdic".parse::<Charset>().is_err());
+}
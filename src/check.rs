@@ -0,0 +1,164 @@
+//! Functions for analyzing a passphrase the user already has, reporting the entropy Phraze would
+//! assign to it if Phraze had generated it.
+use crate::decompose::{Decomposition, WordLookup, decompose};
+use crate::fetch_list;
+use crate::list_info::{BUILT_IN_LISTS, min_entropy_per_word, shannon_entropy_per_word};
+use crate::separators::is_separator_symbol;
+use std::collections::HashSet;
+
+/// Most ways a passphrase with no separator can be split into words that we'll bother finding
+const MAXIMUM_DECOMPOSITIONS: usize = 1000;
+
+/// Print a report on the given passphrase: what kinds of characters it has, which word lists its
+/// words could have come from, and how much entropy Phraze would say it has. If the custom list
+/// is weighted, give the weight of each of its words too.
+pub fn print_check_report(
+    passphrase: &str,
+    custom_list: Option<&[String]>,
+    custom_weights: Option<&[f64]>,
+) {
+    println!(
+        "Passphrase is {} characters long",
+        passphrase.chars().count()
+    );
+    println!(
+        "Contains uppercase letters: {}",
+        yes_or_no(passphrase.chars().any(char::is_uppercase))
+    );
+    println!(
+        "Contains digits: {}",
+        yes_or_no(passphrase.chars().any(|c| c.is_ascii_digit()))
+    );
+    println!(
+        "Contains symbols: {}",
+        yes_or_no(passphrase.chars().any(is_separator_symbol))
+    );
+
+    let mut found_words = false;
+    for built_in_list in BUILT_IN_LISTS {
+        found_words |= report_on_list(
+            passphrase,
+            built_in_list.name,
+            fetch_list(built_in_list.list_choice),
+            None,
+        );
+    }
+    if let Some(custom_list) = custom_list {
+        found_words |= report_on_list(passphrase, "Custom list", custom_list, custom_weights);
+    }
+
+    if !found_words {
+        let (entropy, pool_size) = character_level_entropy_upper_bound(passphrase);
+        println!(
+            "Couldn't break passphrase into words from any list. As {} characters drawn from a pool of {} characters, it has at most {:.2} bits of entropy. That's an upper bound: if it's made of words, names, dates or patterns, it has far less",
+            passphrase.chars().count(),
+            pool_size,
+            entropy
+        );
+    }
+}
+
+/// Try to break passphrase into words from the given list, printing what we find.
+/// Returns whether we found any words.
+fn report_on_list<T: AsRef<str>>(
+    passphrase: &str,
+    list_name: &str,
+    list: &[T],
+    weights: Option<&[f64]>,
+) -> bool {
+    let lookup = WordLookup::new(list);
+    let decompositions = decompose(passphrase, &lookup, MAXIMUM_DECOMPOSITIONS);
+    // If there's more than one way to read the passphrase, be conservative and go with
+    // the reading that uses the fewest words
+    let decomposition: &Decomposition =
+        match decompositions.iter().min_by_key(|d| d.word_indexes.len()) {
+            Some(decomposition) => decomposition,
+            None => return false,
+        };
+    let number_of_words = decomposition.word_indexes.len();
+    println!(
+        "\n{} ({} words): found {} words with {}{}",
+        list_name,
+        list.len(),
+        number_of_words,
+        decomposition.separator,
+        if decomposition.title_case {
+            ", in Title Case"
+        } else {
+            ""
+        }
+    );
+    // Phraze sizes passphrases from a weighted list by their min-entropy
+    match weights {
+        Some(weights) => println!(
+            "Phraze would estimate {:.2} bits of min-entropy for this passphrase ({:.2} bits of Shannon entropy), since the list is weighted",
+            min_entropy_per_word(weights) * number_of_words as f64,
+            shannon_entropy_per_word(weights) * number_of_words as f64
+        ),
+        None => println!(
+            "Phraze would estimate {:.2} bits of entropy for this passphrase",
+            (list.len() as f64).log2() * number_of_words as f64
+        ),
+    }
+    if decompositions.len() > 1 {
+        println!(
+            "Note: Passphrase can be broken into words from this list in more than one way, so this estimate uses the way with the fewest words"
+        );
+    }
+    true
+}
+
+/// The most entropy a passphrase could have: what it would have if each of its characters had
+/// been picked at random from a pool made up of every class of character that appears in it.
+/// Returns that entropy and the size of the pool. A passphrase someone made up almost never comes
+/// close to this (twenty "a"s would get 94 bits), so it should only ever be reported as an upper
+/// bound, never as an estimate.
+pub fn character_level_entropy_upper_bound(passphrase: &str) -> (f64, usize) {
+    let mut pool_size = 0;
+    if passphrase.chars().any(|c| c.is_ascii_lowercase()) {
+        pool_size += 26;
+    }
+    if passphrase.chars().any(|c| c.is_ascii_uppercase()) {
+        pool_size += 26;
+    }
+    if passphrase.chars().any(|c| c.is_ascii_digit()) {
+        pool_size += 10;
+    }
+    // All printable ASCII punctuation, plus space
+    if passphrase
+        .chars()
+        .any(|c| c.is_ascii_punctuation() || c == ' ')
+    {
+        pool_size += 33;
+    }
+    // Any other characters we just count one by one
+    let other_characters: HashSet<char> = passphrase
+        .chars()
+        .filter(|c| !c.is_ascii_alphanumeric() && !c.is_ascii_punctuation() && *c != ' ')
+        .collect();
+    pool_size += other_characters.len();
+
+    let entropy = if pool_size == 0 {
+        0.0
+    } else {
+        (pool_size as f64).log2() * passphrase.chars().count() as f64
+    };
+    (entropy, pool_size)
+}
+
+fn yes_or_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+#[test]
+fn can_find_upper_bound_of_character_level_entropy() {
+    assert_eq!(character_level_entropy_upper_bound(""), (0.0, 0));
+    assert_eq!(
+        character_level_entropy_upper_bound("abcd"),
+        (26_f64.log2() * 4.0, 26)
+    );
+    assert_eq!(
+        character_level_entropy_upper_bound("aB3!"),
+        (95_f64.log2() * 4.0, 95)
+    );
+}
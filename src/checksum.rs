@@ -0,0 +1,50 @@
+//! An optional checksum word, appended to the end of a passphrase, that lets users catch typos
+//! when re-typing a passphrase they've written down. Like BIP-39, the checksum is derived from a
+//! SHA-256 hash of the words that come before it.
+//!
+//! Since the checksum word is determined entirely by the other words, it adds no entropy.
+use sha2::{Digest, Sha256};
+
+/// Calculate which word on a list of `list_length` words should be used as the checksum word for
+/// a passphrase made up of the given word indexes.
+pub fn checksum_word_index(word_indexes: &[usize], list_length: usize) -> usize {
+    let mut hasher = Sha256::new();
+    for index in word_indexes {
+        hasher.update((*index as u32).to_be_bytes());
+    }
+    let hash = hasher.finalize();
+    let mut first_eight_bytes = [0; 8];
+    first_eight_bytes.copy_from_slice(&hash[..8]);
+    (u64::from_be_bytes(first_eight_bytes) % list_length as u64) as usize
+}
+
+/// Check whether the last of the given word indexes is the correct checksum word for the words
+/// that come before it.
+pub fn has_valid_checksum_word(word_indexes: &[usize], list_length: usize) -> bool {
+    match word_indexes.split_last() {
+        Some((checksum_index, rest)) if !rest.is_empty() => {
+            *checksum_index == checksum_word_index(rest, list_length)
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn can_verify_a_checksum_word() {
+    let list_length = 8192;
+    let mut word_indexes = vec![12, 8000, 0, 4321, 77];
+    word_indexes.push(checksum_word_index(&word_indexes, list_length));
+    assert!(has_valid_checksum_word(&word_indexes, list_length));
+
+    // A typo in one of the words should (almost always) be caught
+    word_indexes[1] = 8001;
+    assert!(!has_valid_checksum_word(&word_indexes, list_length));
+
+    // Swapping the order of words should be caught too
+    let mut word_indexes = vec![12, 8000, 0, 4321, 77];
+    word_indexes.push(checksum_word_index(&word_indexes, list_length));
+    word_indexes.swap(0, 1);
+    assert!(!has_valid_checksum_word(&word_indexes, list_length));
+
+    assert!(!has_valid_checksum_word(&[5], list_length));
+}
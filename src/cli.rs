@@ -1,22 +1,72 @@
-use clap::Parser;
+use crate::alphabets::Alphabet;
+use crate::casing::Locale;
+use crate::charset::Charset;
+use crate::keyboard_layouts::KeyboardLayout;
+use crate::list_choice::{ListChoice, ListMetadata};
+use crate::presets::Preset;
+use crate::pseudo_words::{DEFAULT_PATTERN, SyllablePattern};
+use crate::templates::Template;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, Parser, Subcommand};
 use std::path::PathBuf;
 
-/// This enum, `ListChoice`, represents all of the "built-in" word lists that Phraze can use.
-#[derive(Clone, Debug, Copy)]
-pub enum ListChoice {
-    Long,
-    Medium,
-    Eff,
-    Mnemonicode,
-    Effshort,
-    Qwerty,
-    Alpha,
+/// A word list chosen with --list: either one of the built-in lists, or the name of a list the
+/// user has put in one of their word list directories (see the `user_lists` module).
+#[derive(Clone, Debug)]
+pub enum ListSelection {
+    BuiltIn(ListChoice),
+    Named(String),
 }
 
+/// Name of each built-in word list, plus an optional note about it, used in help text. The number
+/// of words on each list is filled in from the lists themselves (see `list_choice_help`).
+pub const LIST_OPTIONS: &[(ListChoice, &str, &str)] = &[
+    #[cfg(feature = "list-medium")]
+    (ListChoice::Medium, "Orchard Street Medium List", ""),
+    #[cfg(feature = "list-long")]
+    (ListChoice::Long, "Orchard Street Long List", ""),
+    #[cfg(feature = "list-eff")]
+    (ListChoice::Eff, "EFF Long List", ""),
+    #[cfg(feature = "list-mnemonicode")]
+    (
+        ListChoice::Mnemonicode,
+        "Mnemonicode list",
+        "Good if you know you're going to be speaking passphrases out loud.",
+    ),
+    #[cfg(feature = "list-eff-short")]
+    (ListChoice::Effshort, "EFF Short List", ""),
+    #[cfg(feature = "list-qwerty")]
+    (
+        ListChoice::Qwerty,
+        "Orchard Street QWERTY List",
+        "Optimized to minimize travel distance on QWERTY keyboard layout.",
+    ),
+    #[cfg(feature = "list-alpha")]
+    (
+        ListChoice::Alpha,
+        "Orchard Street Alpha List",
+        "Optimized to minimize travel distance on alphabetical keyboard layout.",
+    ),
+];
+
+/// The first built-in list is the default. That's the Orchard Street Medium List, if it's built in.
+const DEFAULT_LIST_CODE: &str = match ListChoice::ALL.first() {
+    Some(list_choice) => list_choice.code(),
+    None => "",
+};
+
 /// Generate random passphrases
 #[derive(Parser, Debug)]
-#[clap(version, name = "phraze")]
+#[clap(version, name = "phraze", args_conflicts_with_subcommands = true)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
+    /// Use settings made for a common use of a passphrase. Any other options given override the
+    /// preset's settings.
+    #[clap(long = "preset", long_help = preset_help())]
+    pub preset: Option<Preset>,
+
     /// Strengthen your passphrase the easy way: Each -S flag increases minimum entropy by 20 bits (above the default of
     /// 80 bits).
     #[clap(short = 'S', long = "strength", conflicts_with = "number_of_words", conflicts_with = "minimum_entropy", action = clap::ArgAction::Count)]
@@ -60,56 +110,345 @@ pub struct Args {
     pub separator: String,
 
     /// Choose a word list to use.
-    ///
-    /// Options:
-    ///
-    /// m: Orchard Street Medium List (8,192 words) [DEFAULT]
-    ///
-    /// l: Orchard Street Long List (17,576 words)
-    ///
-    /// e: EFF Long List (7,776 words)
-    ///
-    /// n: Mnemonicode list (1,633 words). Good if you know you're going to be speaking
-    /// passphrases out loud.
-    ///
-    /// s: EFF Short List (1,296 words)
-    ///
-    /// q: Orchard Street QWERTY List (1,296 words). Optimized to minimize travel
-    /// distance on QWERTY keyboard layout.
-    ///
-    /// a: Orchard Street Alpha List (1,296 words). Optimized to minimize travel
-    /// distance on alphabetical keyboard layout.
-    #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value="m")]
-    pub list_choice: ListChoice,
+    #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE, long_help=list_choice_help(None))]
+    pub list_choice: ListSelection,
 
     /// Provide a text file with a list of words to randomly generate passphrase
-    /// from. Should be a text file with one word per line.
-    #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
-    pub custom_list_file_path: Option<PathBuf>,
+    /// from. Should be a text file with one word per line, or, under a "# weighted: true" header,
+    /// a word, a tab and a weight per line to pick some words more often. Give more than one to
+    /// merge them. Use along with --list to merge them with that list too.
+    #[clap(short = 'c', long = "custom-list")]
+    pub custom_list_file_paths: Vec<PathBuf>,
+
+    /// Refuse to use the custom word list unless the file has this SHA-256 hash, as printed by
+    /// sha256sum. Lists can also be pinned to a hash in ~/.config/phraze/pinned-lists.
+    #[clap(long = "custom-list-sha256")]
+    pub custom_list_sha256: Option<String>,
+
+    /// Make passphrases that follow this template of parts of speech, like "adj noun verb adv".
+    /// Each word is picked from the words tagged with its slot's part of speech on a tagged list,
+    /// given with --custom-list. The template repeats if more words are needed.
+    #[clap(long = "template")]
+    pub template: Option<Template>,
+
+    /// Make passphrases out of pronounceable pseudo-words instead of words from a list. Optionally
+    /// give the pattern of consonants (c) and vowels (v) each pseudo-word follows, which is
+    /// cvcvc by default. --words sets the number of pseudo-words.
+    #[clap(long = "pseudo-words", num_args = 0..=1, default_missing_value = DEFAULT_PATTERN, conflicts_with_all = ["custom_list_file_paths", "template"], value_name = "PATTERN")]
+    pub pseudo_words: Option<SyllablePattern>,
+
+    /// Make a random password of characters instead of a passphrase, picked from this alphabet:
+    /// lowercase, alnum (the default), printable (ASCII, without space), shell-safe (letters,
+    /// digits and punctuation a shell never needs quoted), or custom: followed by the characters
+    /// to use. --words sets the number of characters.
+    #[clap(long = "chars", num_args = 0..=1, default_missing_value = "alnum", conflicts_with_all = ["custom_list_file_paths", "template", "pseudo_words"], value_name = "ALPHABET")]
+    pub chars: Option<Alphabet>,
+
+    /// Smallest custom word list Phraze will make passphrases from. A short list makes for long
+    /// passphrases, and is often a sign that the wrong file was given.
+    #[clap(long = "min-list-size", default_value = "1000")]
+    pub minimum_list_size: usize,
+
+    /// Longest the generated passphrase may be, in characters. Words too long to ever fit are left
+    /// off the list, so that the entropy estimate stays accurate.
+    #[clap(long = "max-length")]
+    pub maximum_length: Option<usize>,
+
+    /// Shortest the generated passphrase may be, in characters. If the passphrase could come out
+    /// shorter, words are added until even one made of the shortest words is long enough.
+    #[clap(long = "min-length")]
+    pub minimum_length: Option<usize>,
+
+    /// Only use characters from this charset: ascii (printable ASCII), alnum (ASCII letters and
+    /// digits), latin1 (printable Latin-1), or custom: followed by the characters to allow, like
+    /// custom:abcdefghijklmnopqrstuvwxyz-. Words that can't be written in the charset are left
+    /// off the list, and random separators are only picked from it.
+    #[clap(long = "charset")]
+    pub charset: Option<Charset>,
 
     /// Use Title Case for words in generated passphrase
     #[clap(short = 't', long = "title-case")]
     pub title_case: bool,
 
+    /// Use the casing rules of this language for --title-case. Only needed for languages whose
+    /// rules differ from Unicode's defaults: tr (Turkish) and az (Azerbaijani), where the capital
+    /// of i is İ, and nl (Dutch), where a starting "ij" becomes "IJ".
+    #[clap(long = "locale")]
+    pub locale: Option<Locale>,
+
+    /// Add a checksum word to the end of generated passphrase, so that typos can be caught later
+    /// with `phraze verify`. The checksum word doesn't add any entropy.
+    #[clap(long = "checksum-word")]
+    pub checksum_word: bool,
+
     /// Print estimated entropy of generated passphrase, in bits, along with
     /// the passphrase itself
     #[clap(short = 'v', long = "verbose")]
     pub verbose: bool,
+
+    /// Print how much effort it takes to type each generated passphrase on the given keyboard
+    /// layout, measured as the distance travelled from key to key. Options: qwerty, azerty,
+    /// qwertz, dvorak, colemak, and alpha (an alphabetical on-screen keyboard).
+    #[clap(long = "layout")]
+    pub layout: Option<KeyboardLayout>,
 }
 
-/// Convert list_choice string slice into a ListChoice enum. Clap calls this function.
-fn parse_list_choice(list_choice: &str) -> Result<ListChoice, String> {
-    match list_choice.to_lowercase().as_ref() {
-        "l" => Ok(ListChoice::Long),
-        "m" => Ok(ListChoice::Medium),
-        "e" => Ok(ListChoice::Eff),
-        "n" => Ok(ListChoice::Mnemonicode),
-        "s" => Ok(ListChoice::Effshort),
-        "q" => Ok(ListChoice::Qwerty),
-        "a" => Ok(ListChoice::Alpha),
-        _ => Err(format!(
+/// Things Phraze can do other than generate passphrases
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Analyze an existing passphrase, read from stdin. Phraze tries to break it into words from
+    /// each of its built-in word lists, then reports the entropy it would estimate if it had
+    /// generated the passphrase itself.
+    Check {
+        /// Also try to break the passphrase into words from this word list file
+        #[clap(short = 'c', long = "custom-list")]
+        custom_list_file_path: Option<PathBuf>,
+    },
+    /// Break a passphrase that has no separators, read from stdin, back into its words. Prints
+    /// every possible way to split it, if there's more than one.
+    Split {
+        /// Word list the passphrase was made from. Accepts the same options as --list above.
+        #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE)]
+        list_choice: ListSelection,
+
+        /// Word list file the passphrase was made from
+        #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+        custom_list_file_path: Option<PathBuf>,
+    },
+    /// Check that the last word of a passphrase, read from stdin, is the correct checksum word
+    /// (see --checksum-word above)
+    Verify {
+        /// Word list the passphrase was made from. Accepts the same options as --list above.
+        #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE)]
+        list_choice: ListSelection,
+
+        /// Word list file the passphrase was made from
+        #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+        custom_list_file_path: Option<PathBuf>,
+    },
+    /// Recover a mistyped or half-remembered passphrase. Prints a ranked list of candidate
+    /// passphrases made of words from the list that are close to the words given.
+    Recover {
+        /// What you remember of the passphrase, like "tabel-orange-?-horse". Use a ? in place of
+        /// any word you can't remember at all. If not given, it's read from stdin.
+        pattern: Option<String>,
+
+        /// Word list the passphrase was made from. Accepts the same options as --list above.
+        #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE)]
+        list_choice: ListSelection,
+
+        /// Word list file the passphrase was made from
+        #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+        custom_list_file_path: Option<PathBuf>,
+
+        /// Count typos of neighboring keys on this keyboard layout as smaller typos. Defaults to
+        /// QWERTY for the QWERTY list (q) and alpha for the Alpha list (a).
+        #[clap(long = "layout")]
+        layout: Option<KeyboardLayout>,
+
+        /// Word separator to use in candidate passphrases. Defaults to the separator used in
+        /// the given pattern.
+        #[clap(short = 's', long = "sep")]
+        separator: Option<String>,
+
+        /// Most typos (insertions, deletions, substitutions or swaps) to allow in each word
+        #[clap(short = 'd', long = "max-edits", default_value = "2")]
+        maximum_edits: u32,
+
+        /// Most candidate passphrases to print
+        #[clap(short = 'm', long = "max-candidates", default_value = "1000")]
+        maximum_candidates: usize,
+    },
+    /// List every word list available: the built-in lists, plus any lists in the user's word
+    /// list directories
+    Lists,
+    /// Encode bytes, read from stdin, as words. Each word on a list of N words stands for a digit
+    /// in base N. Leading zero bytes are kept, so bytes survive a trip through `phraze decode`
+    /// unchanged.
+    Encode {
+        /// Word list to encode bytes with. Accepts the same options as --list above.
+        #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE)]
+        list_choice: ListSelection,
+
+        /// Word list file to encode bytes with
+        #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+        custom_list_file_path: Option<PathBuf>,
+
+        /// Word separator
+        #[clap(short = 's', long = "sep", default_value = "-")]
+        separator: String,
+
+        /// Read input as hexadecimal text rather than raw bytes
+        #[clap(long = "hex")]
+        hex: bool,
+    },
+    /// Decode words, read from stdin, back into the bytes they were encoded from with
+    /// `phraze encode`. Raw bytes are written to stdout.
+    Decode {
+        /// Word list the bytes were encoded with. Accepts the same options as --list above.
+        #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value=DEFAULT_LIST_CODE)]
+        list_choice: ListSelection,
+
+        /// Word list file the bytes were encoded with
+        #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+        custom_list_file_path: Option<PathBuf>,
+
+        /// Write output as hexadecimal text rather than raw bytes
+        #[clap(long = "hex")]
+        hex: bool,
+    },
+}
+
+impl Args {
+    /// Like `Args::command()`, except that the help text for --list also gives the number of
+    /// words on each built-in list, and how much entropy each word adds. build.rs measures these
+    /// from the lists themselves, so they're passed in here.
+    pub fn command_with_list_metadata(
+        list_metadata: &dyn Fn(ListChoice) -> ListMetadata,
+    ) -> clap::Command {
+        Args::command().mut_arg("list_choice", |arg| {
+            arg.long_help(list_choice_help(Some(list_metadata)))
+        })
+    }
+
+    /// Fill in the settings of the chosen --preset, except for any the user gave on the command
+    /// line themselves. `matches` must be the matches these `Args` were made from.
+    pub fn apply_preset(&mut self, matches: &ArgMatches) {
+        let Some(preset) = self.preset else {
+            return;
+        };
+        let settings = preset.settings();
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+        if !given("number_of_words") && !given("minimum_entropy") && !given("strength_count") {
+            self.minimum_entropy = Some(settings.minimum_entropy);
+        }
+        if !given("separator") {
+            self.separator = settings.separator.to_string();
+        }
+        if !given("title_case") {
+            self.title_case = settings.title_case;
+        }
+        if let Some(list_choice) = settings.list_choice
+            && !given("list_choice")
+            && !given("custom_list_file_paths")
+        {
+            self.list_choice = ListSelection::BuiltIn(list_choice);
+        }
+        if !given("maximum_length") {
+            self.maximum_length = settings.maximum_length;
+        }
+        if !given("minimum_length") {
+            self.minimum_length = settings.minimum_length;
+        }
+        if !given("charset") {
+            self.charset = settings.charset;
+        }
+    }
+}
+
+/// Help text for --preset, describing each preset
+fn preset_help() -> String {
+    let mut help = "Use settings made for a common use of a passphrase. Any other options given \
+        override the preset's settings.\n\nOptions:"
+        .to_string();
+    for preset in Preset::ALL {
+        help += &format!("\n\n{}: {}", preset, preset.description());
+    }
+    help
+}
+
+/// Build the help text for the --list option, listing only the lists that are built in. If
+/// `list_metadata` is given, each list's word count and bits per word are included too.
+fn list_choice_help(list_metadata: Option<&dyn Fn(ListChoice) -> ListMetadata>) -> String {
+    let mut help = "Choose a word list to use.\n\nOptions:".to_string();
+    for (list_choice, name, note) in LIST_OPTIONS {
+        help += &format!("\n\n{} or {}: {}", list_choice.code(), list_choice, name);
+        if let Some(list_metadata) = list_metadata {
+            let metadata = list_metadata(*list_choice);
+            help += &format!(
+                " ({} words, {:.2} bits per word)",
+                with_thousands_separators(metadata.word_count),
+                metadata.bits_per_word
+            );
+        }
+        if !note.is_empty() {
+            help += &format!(". {}", note);
+        }
+        if list_choice.code() == DEFAULT_LIST_CODE {
+            help += " [DEFAULT]";
+        }
+    }
+    help += "\n\nYou can also give the name of a word list in your word list directory \
+        (~/.local/share/phraze/lists) or in a directory in $PHRAZE_LIST_PATH. \
+        Run `phraze lists` to see every list available.";
+    help
+}
+
+/// Format a number like 17576 as "17,576"
+fn with_thousands_separators(n: usize) -> String {
+    let digits = n.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Convert list_choice string slice into a ListSelection enum. Clap calls this function.
+/// Built-in lists can be picked by one-letter code or by name; anything else is taken as the name of a user word list.
+fn parse_list_choice(list_choice: &str) -> Result<ListSelection, String> {
+    if let Ok(built_in_list) = list_choice.parse::<ListChoice>() {
+        return Ok(ListSelection::BuiltIn(built_in_list));
+    }
+    // User word list names become file names, so keep them simple
+    let is_valid_name = !list_choice.is_empty()
+        && !list_choice.starts_with('.')
+        && list_choice
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.');
+    if is_valid_name {
+        Ok(ListSelection::Named(list_choice.to_string()))
+    } else {
+        Err(format!(
             "Inputted list choice '{}' doesn't correspond to an available word list",
             list_choice
-        )),
+        ))
     }
 }
+
+#[test]
+fn can_add_thousands_separators() {
+    assert_eq!(with_thousands_separators(0), "0");
+    assert_eq!(with_thousands_separators(1296), "1,296");
+    assert_eq!(with_thousands_separators(17576), "17,576");
+    assert_eq!(with_thousands_separators(1234567), "1,234,567");
+}
+
+#[test]
+fn flags_given_by_user_override_preset() {
+    use clap::FromArgMatches;
+    let args_from = |command_line: &[&str]| {
+        let matches = Args::command().get_matches_from(command_line);
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        args.apply_preset(&matches);
+        args
+    };
+
+    let args = args_from(&["phraze", "--preset", "disk"]);
+    assert_eq!(args.minimum_entropy, Some(100));
+    assert_eq!(args.separator, " ");
+
+    let args = args_from(&["phraze", "--preset", "disk", "-s", "_", "-w", "5"]);
+    assert_eq!(args.minimum_entropy, None);
+    assert_eq!(args.number_of_words, Some(5));
+    assert_eq!(args.separator, "_");
+
+    let args = args_from(&["phraze", "--preset", "wifi", "--max-length", "40"]);
+    assert_eq!(args.maximum_length, Some(40));
+    assert_eq!(args.minimum_length, Some(8));
+    assert_eq!(args.charset, Some(Charset::Ascii));
+}
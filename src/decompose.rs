@@ -0,0 +1,491 @@
+//! Functions for breaking an existing passphrase back down into the words (and separators) it was
+//! made from. This is the reverse of what Phraze usually does, and is used when a user brings us
+//! a passphrase they already have.
+use crate::make_title_case;
+use crate::separators::is_separator_symbol;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A quick way to look up whether a given string is a word on a word list, and if so, where on
+/// the list it is. Look-ups are case-insensitive.
+pub struct WordLookup {
+    indexes: HashMap<String, usize>,
+    longest_word_length: usize,
+    /// Every character that appears in a word on the list
+    word_chars: HashSet<char>,
+}
+
+impl WordLookup {
+    pub fn new<T: AsRef<str>>(list: &[T]) -> Self {
+        let mut indexes = HashMap::new();
+        let mut longest_word_length = 0;
+        let mut word_chars = HashSet::new();
+        for (index, word) in list.iter().enumerate() {
+            let word = word.as_ref().to_lowercase();
+            longest_word_length = longest_word_length.max(word.chars().count());
+            word_chars.extend(word.chars());
+            indexes.entry(word).or_insert(index);
+        }
+        WordLookup {
+            indexes,
+            longest_word_length,
+            word_chars,
+        }
+    }
+
+    /// Returns the index of given word on the list, if it's on the list.
+    pub fn index_of(&self, word: &str) -> Option<usize> {
+        self.indexes.get(&word.to_lowercase()).copied()
+    }
+
+    /// Returns true if given character appears in any word on the list, in any case. Words on
+    /// some lists have hyphens ("t-shirt") or digits in them, so this can't just check whether
+    /// the character is a letter.
+    pub fn is_word_char(&self, c: char) -> bool {
+        self.word_chars.contains(&c) || c.to_lowercase().all(|c| self.word_chars.contains(&c))
+    }
+}
+
+/// The different kinds of separators Phraze can put between words.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeparatorKind {
+    None,
+    Fixed(String),
+    RandomNumbers,
+    RandomSymbols,
+    RandomNumbersAndSymbols,
+}
+
+impl fmt::Display for SeparatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeparatorKind::None => write!(f, "no separator"),
+            SeparatorKind::Fixed(separator) => write!(f, "'{}' as separator", separator),
+            SeparatorKind::RandomNumbers => write!(f, "random numbers as separators (_n)"),
+            SeparatorKind::RandomSymbols => write!(f, "random symbols as separators (_s)"),
+            SeparatorKind::RandomNumbersAndSymbols => {
+                write!(f, "random numbers and symbols as separators (_b)")
+            }
+        }
+    }
+}
+
+/// One way of reading a passphrase as words from a given list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    pub word_indexes: Vec<usize>,
+    pub separator: SeparatorKind,
+    pub title_case: bool,
+}
+
+/// Find every way the given passphrase could have been made by Phraze from the given word list,
+/// stopping after `limit` different ways.
+///
+/// If the passphrase has separators between its words, there's at most one way. If it has no
+/// separators, and the list isn't uniquely decodable, there may be more than one.
+pub fn decompose(passphrase: &str, lookup: &WordLookup, limit: usize) -> Vec<Decomposition> {
+    let passphrase = passphrase.trim();
+    let decompositions = decompose_by_runs(passphrase, lookup, limit);
+    if !decompositions.is_empty() {
+        return decompositions;
+    }
+    // The separator might be made of characters that are also in words, like the hyphens in
+    // "t-shirt-yo-yo" made from the EFF list, so it couldn't be told apart from the words above
+    decompose_with_separators_in_words(passphrase, lookup, limit)
+}
+
+/// Break the passphrase into runs of word characters and separator characters, then look up
+/// each run of word characters on the list
+fn decompose_by_runs(passphrase: &str, lookup: &WordLookup, limit: usize) -> Vec<Decomposition> {
+    let runs = split_into_runs(passphrase, lookup);
+    // Phraze never starts or ends a passphrase with a separator
+    match (runs.first(), runs.last()) {
+        (Some((true, _)), Some((true, _))) => {}
+        _ => return vec![],
+    }
+
+    if runs.len() == 1 {
+        let letters = &runs[0].1;
+        if letters.chars().any(char::is_uppercase) {
+            // With Title Case and no separator, each capital letter marks the start of a word
+            return match words_from_tokens(&split_before_uppercase(letters), lookup) {
+                Some((word_indexes, true)) => vec![Decomposition {
+                    word_indexes,
+                    separator: SeparatorKind::None,
+                    title_case: true,
+                }],
+                _ => vec![],
+            };
+        }
+        return segment(letters, lookup, limit)
+            .into_iter()
+            .map(|word_indexes| Decomposition {
+                word_indexes,
+                separator: SeparatorKind::None,
+                title_case: false,
+            })
+            .collect();
+    }
+
+    let (words, separators): (Vec<_>, Vec<_>) = runs.into_iter().partition(|(is_word, _)| *is_word);
+    let words: Vec<String> = words.into_iter().map(|(_, run)| run).collect();
+    let separators: Vec<String> = separators.into_iter().map(|(_, run)| run).collect();
+    let separator = match classify_separators(&separators) {
+        Some(separator) => separator,
+        None => return vec![],
+    };
+    match words_from_tokens(&words, lookup) {
+        Some((word_indexes, title_case)) => vec![Decomposition {
+            word_indexes,
+            separator,
+            title_case,
+        }],
+        None => vec![],
+    }
+}
+
+/// Find ways of reading the passphrase as words with a one-character separator between each
+/// pair of them, where the separator characters also appear in words on the list. Each
+/// non-letter character in the passphrase is tried as a fixed separator, and then random numbers
+/// and symbols.
+fn decompose_with_separators_in_words(
+    passphrase: &str,
+    lookup: &WordLookup,
+    limit: usize,
+) -> Vec<Decomposition> {
+    let chars: Vec<char> = passphrase.chars().collect();
+    let mut candidates: Vec<char> = chars
+        .iter()
+        .copied()
+        .filter(|c| !c.is_alphabetic() && lookup.is_word_char(*c))
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    let mut separator_rules: Vec<Box<dyn Fn(char) -> bool>> = candidates
+        .into_iter()
+        .map(|candidate| Box::new(move |c| c == candidate) as Box<dyn Fn(char) -> bool>)
+        .collect();
+    separator_rules.push(Box::new(|c| c.is_ascii_digit() || is_separator_symbol(c)));
+
+    let mut decompositions: Vec<Decomposition> = vec![];
+    for is_separator in separator_rules {
+        for spans in segment_with_separators(&chars, lookup, &is_separator, limit) {
+            let words: Vec<String> = spans
+                .iter()
+                .map(|(start, end)| chars[*start..*end].iter().collect())
+                .collect();
+            let separators: Vec<String> = spans
+                .windows(2)
+                .map(|pair| chars[pair[0].1..pair[1].0].iter().collect())
+                .collect();
+            let (Some((word_indexes, title_case)), Some(separator)) = (
+                words_from_tokens(&words, lookup),
+                classify_separators(&separators),
+            ) else {
+                continue;
+            };
+            let decomposition = Decomposition {
+                word_indexes,
+                separator,
+                title_case,
+            };
+            if !decompositions.contains(&decomposition) {
+                decompositions.push(decomposition);
+            }
+            if decompositions.len() >= limit {
+                return decompositions;
+            }
+        }
+    }
+    decompositions
+}
+
+/// Like `segment`, but with exactly one character, for which `is_separator` returns true,
+/// between each pair of words. Returns the start and end of each word, as `char` positions.
+fn segment_with_separators(
+    chars: &[char],
+    lookup: &WordLookup,
+    is_separator: &dyn Fn(char) -> bool,
+    limit: usize,
+) -> Vec<Vec<(usize, usize)>> {
+    let n = chars.len();
+    // For every position, the words that start there and are followed by either the end of the
+    // passphrase, or a separator and then more words all the way to the end
+    let mut next_words: Vec<Vec<usize>> = vec![vec![]; n + 1];
+    let mut reaches_end = vec![false; n + 2];
+    for start in (0..n).rev() {
+        for end in start + 1..=n.min(start + lookup.longest_word_length) {
+            let followed_by_more_words =
+                end == n || (end + 1 < n && is_separator(chars[end]) && reaches_end[end + 1]);
+            if !followed_by_more_words {
+                continue;
+            }
+            let candidate: String = chars[start..end].iter().collect();
+            if lookup.index_of(&candidate).is_some() {
+                next_words[start].push(end);
+            }
+        }
+        reaches_end[start] = !next_words[start].is_empty();
+    }
+
+    let mut segmentations = vec![];
+    let mut stack: Vec<(usize, Vec<(usize, usize)>)> = vec![(0, vec![])];
+    while let Some((start, spans)) = stack.pop() {
+        if segmentations.len() >= limit {
+            break;
+        }
+        for end in &next_words[start] {
+            let mut spans = spans.clone();
+            spans.push((start, *end));
+            if *end == n {
+                segmentations.push(spans);
+            } else {
+                stack.push((end + 1, spans));
+            }
+        }
+    }
+    segmentations
+}
+
+/// Find every way the given string can be broken into words from the list, assuming there's no
+/// separator between the words. Stops after finding `limit` segmentations.
+/// Each segmentation is returned as a list of word indexes.
+pub fn segment(s: &str, lookup: &WordLookup, limit: usize) -> Vec<Vec<usize>> {
+    let chars: Vec<char> = s.chars().collect();
+    let n = chars.len();
+    // For every position in the string, gather up the words that start there AND are followed
+    // by something we can break into words all the way to the end of the string.
+    let mut next_words: Vec<Vec<(usize, usize)>> = vec![vec![]; n + 1];
+    let mut reaches_end = vec![false; n + 1];
+    reaches_end[n] = true;
+    for start in (0..n).rev() {
+        for end in start + 1..=n.min(start + lookup.longest_word_length) {
+            if !reaches_end[end] {
+                continue;
+            }
+            let candidate: String = chars[start..end].iter().collect();
+            if let Some(index) = lookup.index_of(&candidate) {
+                next_words[start].push((index, end));
+            }
+        }
+        reaches_end[start] = !next_words[start].is_empty();
+    }
+
+    let mut segmentations = vec![];
+    if n > 0 {
+        collect_segmentations(0, &next_words, &mut vec![], &mut segmentations, limit);
+    }
+    segmentations
+}
+
+/// Walk every path from `position` to the end of the string, adding each complete one to `found`.
+fn collect_segmentations(
+    position: usize,
+    next_words: &[Vec<(usize, usize)>],
+    current: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+    limit: usize,
+) {
+    if found.len() >= limit {
+        return;
+    }
+    if position == next_words.len() - 1 {
+        found.push(current.clone());
+        return;
+    }
+    for &(index, end) in &next_words[position] {
+        current.push(index);
+        collect_segmentations(end, next_words, current, found, limit);
+        current.pop();
+    }
+}
+
+/// Break a string into alternating runs of "word" characters and separator characters. Each
+/// run is paired with whether or not it's made of word characters. A word character is any
+/// character that appears in a word on the list.
+fn split_into_runs(s: &str, lookup: &WordLookup) -> Vec<(bool, String)> {
+    let mut runs: Vec<(bool, String)> = vec![];
+    for c in s.chars() {
+        let is_word_char = lookup.is_word_char(c);
+        match runs.last_mut() {
+            Some((is_word, run)) if *is_word == is_word_char => run.push(c),
+            _ => runs.push((is_word_char, c.to_string())),
+        }
+    }
+    runs
+}
+
+/// Break a Title Case string like "HelloWorld" into "Hello" and "World".
+fn split_before_uppercase(s: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    for c in s.chars() {
+        match tokens.last_mut() {
+            Some(token) if !c.is_uppercase() => token.push(c),
+            _ => tokens.push(c.to_string()),
+        }
+    }
+    tokens
+}
+
+/// Look up each token on the word list. Since Phraze applies Title Case to either every word or
+/// none of them, the tokens must either all be lowercase or all be Title Case. Returns the word
+/// indexes and whether the tokens are in Title Case.
+fn words_from_tokens(tokens: &[String], lookup: &WordLookup) -> Option<(Vec<usize>, bool)> {
+    let mut word_indexes = vec![];
+    let mut title_case_count = 0;
+    for token in tokens {
+        word_indexes.push(lookup.index_of(token)?);
+        if *token == make_title_case(token) && *token != token.to_lowercase() {
+            title_case_count += 1;
+        } else if *token != token.to_lowercase() {
+            return None;
+        }
+    }
+    match title_case_count {
+        0 => Some((word_indexes, false)),
+        n if n == tokens.len() => Some((word_indexes, true)),
+        _ => None,
+    }
+}
+
+/// Figure out which separator setting could have produced the given separators. Returns `None`
+/// if no single setting could have.
+fn classify_separators(separators: &[String]) -> Option<SeparatorKind> {
+    if separators
+        .iter()
+        .all(|separator| *separator == separators[0])
+    {
+        return Some(SeparatorKind::Fixed(separators[0].clone()));
+    }
+    // Random separators are always exactly one character
+    let mut characters = vec![];
+    for separator in separators {
+        let mut chars = separator.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => characters.push(c),
+            _ => return None,
+        }
+    }
+    if characters.iter().all(char::is_ascii_digit) {
+        Some(SeparatorKind::RandomNumbers)
+    } else if characters.iter().all(|c| is_separator_symbol(*c)) {
+        Some(SeparatorKind::RandomSymbols)
+    } else if characters
+        .iter()
+        .all(|c| c.is_ascii_digit() || is_separator_symbol(*c))
+    {
+        Some(SeparatorKind::RandomNumbersAndSymbols)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn can_decompose_passphrases_with_different_separators_and_casing() {
+    let list = ["alpha", "beta", "charlie", "delta"];
+    let lookup = WordLookup::new(&list);
+
+    let decompositions = decompose("charlie-alpha-delta", &lookup, 10);
+    assert_eq!(decompositions.len(), 1);
+    assert_eq!(decompositions[0].word_indexes, vec![2, 0, 3]);
+    assert_eq!(
+        decompositions[0].separator,
+        SeparatorKind::Fixed("-".to_string())
+    );
+    assert!(!decompositions[0].title_case);
+
+    let decompositions = decompose("Charlie4Alpha&Delta", &lookup, 10);
+    assert_eq!(decompositions[0].word_indexes, vec![2, 0, 3]);
+    assert_eq!(
+        decompositions[0].separator,
+        SeparatorKind::RandomNumbersAndSymbols
+    );
+    assert!(decompositions[0].title_case);
+
+    let decompositions = decompose("BetaBetaAlpha", &lookup, 10);
+    assert_eq!(decompositions[0].word_indexes, vec![1, 1, 0]);
+    assert_eq!(decompositions[0].separator, SeparatorKind::None);
+
+    // Mixed casing isn't something Phraze would make
+    assert!(decompose("Charlie-alpha-delta", &lookup, 10).is_empty());
+    // Nor is a word that's not on the list
+    assert!(decompose("charlie-echo-delta", &lookup, 10).is_empty());
+}
+
+#[test]
+fn can_find_every_segmentation_of_an_ambiguous_string() {
+    let list = ["a", "ab", "b", "ba"];
+    let lookup = WordLookup::new(&list);
+    let segmentations = segment("aba", &lookup, 10);
+    // a-b-a, a-ba, ab-a
+    assert_eq!(segmentations.len(), 3);
+    assert!(segmentations.contains(&vec![0, 2, 0]));
+    assert!(segmentations.contains(&vec![0, 3]));
+    assert!(segmentations.contains(&vec![1, 0]));
+    assert!(segment("abc", &lookup, 10).is_empty());
+}
+
+#[test]
+fn can_decompose_passphrases_from_lists_with_digits_and_hyphens() {
+    let list = ["w0001", "w0002", "w0010"];
+    let lookup = WordLookup::new(&list);
+    let decompositions = decompose("w0001-w0002", &lookup, 10);
+    assert_eq!(decompositions[0].word_indexes, vec![0, 1]);
+    assert_eq!(
+        decompositions[0].separator,
+        SeparatorKind::Fixed("-".to_string())
+    );
+    // A random number separator is made of the same characters as the words
+    let decompositions = decompose("w00107w0001", &lookup, 10);
+    assert_eq!(decompositions[0].word_indexes, vec![2, 0]);
+
+    let list = ["drop-down", "t-shirt", "yo-yo"];
+    let lookup = WordLookup::new(&list);
+    let decompositions = decompose("t-shirt-yo-yo-drop-down", &lookup, 10);
+    assert_eq!(decompositions.len(), 1);
+    assert_eq!(decompositions[0].word_indexes, vec![1, 2, 0]);
+    assert_eq!(
+        decompositions[0].separator,
+        SeparatorKind::Fixed("-".to_string())
+    );
+    let decompositions = decompose("T-shirt_Yo-yo", &lookup, 10);
+    assert_eq!(decompositions[0].word_indexes, vec![1, 2]);
+    assert!(decompositions[0].title_case);
+    assert_eq!(decompose("yo-yo", &lookup, 10)[0].word_indexes, vec![2]);
+    assert!(decompose("t-shirt-yo", &lookup, 10).is_empty());
+}
+
+#[cfg(feature = "list-eff")]
+#[test]
+fn can_decompose_passphrases_with_hyphenated_words_from_the_eff_list() {
+    use crate::fetch_list;
+    use crate::list_choice::ListChoice;
+    let list = fetch_list(ListChoice::Eff);
+    let lookup = WordLookup::new(list);
+    let index = |word: &str| list.iter().position(|w| *w == word).unwrap();
+    let expected = vec![
+        index("t-shirt"),
+        index("drop-down"),
+        index("felt-tip"),
+        index("yo-yo"),
+    ];
+    for (passphrase, separator) in [
+        (
+            "t-shirt-drop-down-felt-tip-yo-yo",
+            SeparatorKind::Fixed("-".to_string()),
+        ),
+        (
+            "t-shirt drop-down felt-tip yo-yo",
+            SeparatorKind::Fixed(" ".to_string()),
+        ),
+        (
+            "t-shirt3drop-down&felt-tip7yo-yo",
+            SeparatorKind::RandomNumbersAndSymbols,
+        ),
+    ] {
+        let decompositions = decompose(passphrase, &lookup, 10);
+        assert_eq!(decompositions.len(), 1, "{}", passphrase);
+        assert_eq!(decompositions[0].word_indexes, expected);
+        assert_eq!(decompositions[0].separator, separator);
+    }
+}
@@ -1,56 +1,410 @@
-//! A couple functions for reading in custom word list files
+//! A couple functions for reading in custom word list files. Lists can also be read from stdin,
+//! and from files compressed with gzip, Zstandard or XZ.
+//!
+//! Lines starting with `#` are comments, and are skipped. Comments at the very top of the file
+//! can also make up a header that describes the list, one `# key: value` line per field:
+//!
+//! ```text
+//! # name: My Word List
+//! # expected-count: 7776
+//! # normalization: NFC
+//! # sha256: 3f0a...
+//! # language: en
+//! # license: CC-BY-SA-4.0
+//! # uniquely-decodable: true
+//! # weighted: true
+//! ```
+//!
+//! Every field is optional. Phraze checks the list against any that make a claim about it (the
+//! count, normalization, hash and unique decodability) and refuses to use a list that doesn't
+//! match, since that usually means the file was cut short or altered. The hash is a SHA-256 of
+//! the list's word lines, with comments and blank lines left out and each line ending in a
+//! newline.
+//!
+//! A list can also be weighted, so that some words come up more often than others. A weighted
+//! list says so in its header, with `# weighted: true`, and then each line is a word, a tab, and
+//! the word's weight, a positive number, like `apple<TAB>3`. A word with a weight of 3 is picked
+//! 3 times as often as a word with a weight of 1. On a list without that header field, a tab is
+//! just part of the line, so a plain list that happens to have tabs in it is still read as-is.
+//!
+//! Lists used with a template (see the `templates` module) are tagged instead: each line is a
+//! word, a tab, and the word's parts of speech, separated by commas, like `light<TAB>adjective,noun`.
 
-use crate::unicode_normalization_check::uniform_unicode_normalization;
+use crate::list_checks::is_uniquely_decodable;
+use crate::mnemonic_encoding::format_hex;
+use crate::templates::{PartOfSpeech, TaggedList};
+use crate::unicode_normalization_check::{
+    NormalizationForm, detect_normalization_form, uniform_unicode_normalization,
+};
+use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::io::Read;
 use std::path::Path;
-use std::path::PathBuf;
-use std::str::FromStr;
 
-/// Read text file into a `Vec<String>`. Also trims whitespace, avoids adding blank strings,
-/// sorts, de-duplicates, and checks for uniform Unicode normalization.
+/// What a custom word list's header says about it. Any field can be left out of the header.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListHeader {
+    pub name: Option<String>,
+    pub expected_count: Option<usize>,
+    pub normalization: Option<NormalizationForm>,
+    pub sha256: Option<String>,
+    pub language: Option<String>,
+    pub license: Option<String>,
+    pub uniquely_decodable: Option<bool>,
+    /// Whether each word on the list is followed by a tab and its weight
+    pub weighted: Option<bool>,
+}
+
+/// A word list, the weight of each of its words if it's a weighted list, and its header
+pub type WeightedList = (Vec<String>, Option<Vec<f64>>, ListHeader);
+
+/// Read text file into a `Vec<String>`. Also trims whitespace, avoids adding blank strings or
+/// comments, sorts, de-duplicates, checks for uniform Unicode normalization, and checks the list
+/// against its header, if it has one. A path of `-` reads the list from stdin, and files ending
+/// in `.gz`, `.zst` or `.xz` are decompressed. Any weights on the list are left out.
 pub fn read_in_custom_list(file_path: &Path) -> Result<Vec<String>, String> {
-    let file_input: Vec<String> = match read_by_line(file_path.to_path_buf()) {
+    let contents = read_list_file(file_path)?;
+    parse_list_file(file_path, &contents).map(|(word_list, _weights, _header)| word_list)
+}
+
+/// Read the raw (possibly compressed) contents of a word list file, or of stdin if the path is
+/// `-`. Reading the contents just once lets us check their hash and then parse them, even when
+/// they come from stdin.
+pub fn read_list_file(file_path: &Path) -> Result<Vec<u8>, String> {
+    let mut contents = vec![];
+    let result = if is_stdin(file_path) {
+        io::stdin().read_to_end(&mut contents).map(|_| ())
+    } else {
+        File::open(file_path).and_then(|mut file| file.read_to_end(&mut contents).map(|_| ()))
+    };
+    match result {
+        Ok(()) => Ok(contents),
+        Err(e) => Err(format!("Error reading word list file: {}", e)),
+    }
+}
+
+/// Make a word list out of the contents of a word list file, read in with `read_list_file`,
+/// and check it against its header. Also returns the weight of each word, if the list is
+/// weighted, and the list's header.
+pub fn parse_list_file(file_path: &Path, contents: &[u8]) -> Result<WeightedList, String> {
+    let file_input: Vec<String> = match read_by_line(file_path, contents) {
         Ok(r) => r,
         Err(e) => return Err(format!("Error reading word list file: {}", e)),
     };
+    parse_list(&file_input)
+}
+
+/// Same as `parse_list_file`, but for a tagged list (see `parse_tagged_list`)
+pub fn parse_tagged_list_file(
+    file_path: &Path,
+    contents: &[u8],
+) -> Result<(TaggedList, ListHeader), String> {
+    let file_input: Vec<String> = match read_by_line(file_path, contents) {
+        Ok(r) => r,
+        Err(e) => return Err(format!("Error reading word list file: {}", e)),
+    };
+    parse_tagged_list(&file_input)
+}
+
+/// Returns true if given path means "read from stdin"
+pub fn is_stdin(file_path: &Path) -> bool {
+    file_path == Path::new("-")
+}
+
+/// Make a word list out of the lines of a word list file, and check it against its header. If
+/// the header says the list is weighted, read each word's weight too. If a word is on a weighted
+/// list more than once, its weights are added together.
+fn parse_list(lines: &[String]) -> Result<WeightedList, String> {
+    let (word_lines, header, sha256) = read_word_lines(lines)?;
+    let weighted = header.weighted == Some(true);
+    let mut weighted_words: Vec<(String, Option<f64>)> = vec![];
+    for (line_number, line) in word_lines {
+        // Remove any starting or trailing whitespace before adding word to list
+        if !weighted {
+            weighted_words.push((line.trim().to_string(), None));
+            continue;
+        }
+        let Some((word, weight)) = line.rsplit_once('\t') else {
+            return Err(format!(
+                "Line {} of word list doesn't have a weight. Lines of a weighted list are a word, a tab, and the word's weight",
+                line_number + 1
+            ));
+        };
+        weighted_words.push((
+            word.trim().to_string(),
+            Some(parse_weight(weight, line_number)?),
+        ));
+    }
+    // Remove any duplicate words, since duplicate words would undermine entropy estimates. On a
+    // weighted list, a duplicate word's weights are added together instead.
+    weighted_words.sort_by(|a, b| a.0.cmp(&b.0));
     let mut word_list: Vec<String> = vec![];
-    for line in file_input {
-        // Don't add blank lines or lines made up purely of whitespace
-        if line.trim() != "" {
-            // Remove any starting or trailing whitespace before adding word to list
-            word_list.push(line.trim().to_string());
+    let mut weights: Vec<f64> = vec![];
+    for (word, weight) in weighted_words {
+        if word_list.last() == Some(&word) {
+            if let (Some(last_weight), Some(weight)) = (weights.last_mut(), weight) {
+                *last_weight += weight;
+            }
+            continue;
+        }
+        word_list.push(word);
+        if let Some(weight) = weight {
+            weights.push(weight);
         }
     }
-    // Remove any duplicate words, since duplicate words would undermine entropy estimates.
-    word_list.sort();
-    word_list.dedup();
-    if !uniform_unicode_normalization(&word_list) {
+    if !word_list.is_empty() && !uniform_unicode_normalization(&word_list) {
         eprintln!(
             "WARNING: Custom word list has multiple Unicode normalizations. Consider normalizing the Unicode of all words on the list before making a passphrase."
         );
     }
-    Ok(word_list)
+    check_list_against_header(&word_list, &sha256, &header)?;
+    let weights = if weighted { Some(weights) } else { None };
+    Ok((word_list, weights, header))
+}
+
+/// Make a tagged word list out of the lines of a custom word list file, and check it against its
+/// header. Each line of a tagged list is a word, a tab, and the word's parts of speech, separated
+/// by commas, like `light<TAB>adjective,noun`.
+fn parse_tagged_list(lines: &[String]) -> Result<(TaggedList, ListHeader), String> {
+    let (word_lines, header, sha256) = read_word_lines(lines)?;
+    let mut tagged_list = TaggedList::default();
+    for (line_number, line) in word_lines {
+        let Some((word, tags)) = line.rsplit_once('\t') else {
+            return Err(format!(
+                "Line {} of word list doesn't have a part of speech. Lines of a tagged list are a word, a tab, and the word's parts of speech",
+                line_number + 1
+            ));
+        };
+        for tag in tags.split(',') {
+            let part_of_speech: PartOfSpeech = tag
+                .parse()
+                .map_err(|e| format!("Line {} of word list: {}", line_number + 1, e))?;
+            tagged_list
+                .words_mut(part_of_speech)
+                .push(word.trim().to_string());
+        }
+    }
+    // Remove any duplicate words, since duplicate words would undermine entropy estimates
+    for part_of_speech in PartOfSpeech::ALL {
+        let words = tagged_list.words_mut(*part_of_speech);
+        words.sort();
+        words.dedup();
+    }
+    let all_words = tagged_list.all_words();
+    if !all_words.is_empty() && !uniform_unicode_normalization(&all_words) {
+        eprintln!(
+            "WARNING: Custom word list has multiple Unicode normalizations. Consider normalizing the Unicode of all words on the list before making a passphrase."
+        );
+    }
+    check_list_against_header(&all_words, &sha256, &header)?;
+    Ok((tagged_list, header))
+}
+
+/// Lines of a word list file that have words on them, with their line numbers (counting from 0)
+type WordLines<'a> = Vec<(usize, &'a str)>;
+
+/// Go through the lines of a word list file, reading its header and skipping comments and blank
+/// lines. Returns the lines that have words on them, along with the header and the SHA-256 hash
+/// of those lines.
+fn read_word_lines(lines: &[String]) -> Result<(WordLines<'_>, ListHeader, String), String> {
+    let mut header = ListHeader::default();
+    let mut in_header = true;
+    let mut hasher = Sha256::new();
+    let mut word_lines = vec![];
+    for (line_number, line) in lines.iter().enumerate() {
+        // Files saved on Windows often start with a byte order mark, and can end lines with
+        // "\r\n" rather than "\n". Neither should end up in a word.
+        let line = match line_number {
+            0 => line.trim_start_matches('\u{feff}'),
+            _ => line,
+        };
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        if let Some(comment) = line.trim_start().strip_prefix('#') {
+            if in_header {
+                read_header_field(comment, &mut header)?;
+            }
+            continue;
+        }
+        // Don't add blank lines or lines made up purely of whitespace
+        if line.trim() == "" {
+            continue;
+        }
+        in_header = false;
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+        word_lines.push((line_number, line));
+    }
+    Ok((word_lines, header, format_hex(&hasher.finalize())))
 }
 
-/// Generic function that reads a text file in as a Vector, line by line.
-/// Not sure if all of the code in this function is necessary, but it gets the job done.
-fn read_by_line<T: FromStr>(file_path: PathBuf) -> io::Result<Vec<T>>
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    let mut vec = Vec::new();
+/// Read the weight of a word on a weighted list. Weights have to be positive, finite numbers.
+fn parse_weight(weight: &str, line_number: usize) -> Result<f64, String> {
+    match weight.trim().parse::<f64>() {
+        Ok(weight) if weight.is_finite() && weight > 0.0 => Ok(weight),
+        _ => Err(format!(
+            "Line {} of word list has an invalid weight: '{}'. Weights must be positive numbers",
+            line_number + 1,
+            weight.trim()
+        )),
+    }
+}
 
-    let f = File::open(file_path)?;
-    let file = BufReader::new(&f);
+/// Merge word lists into one list of every word on any of them. Words are first put in a
+/// common Unicode normalization form, so that the same word spelled in two different forms only
+/// ends up on the merged list once. If every word is already in the same form, that's the one
+/// used. Otherwise it's NFC.
+pub fn merge_word_lists(word_lists: &[Vec<String>]) -> Vec<String> {
+    let all_words: Vec<&String> = word_lists.iter().flatten().collect();
+    let form = detect_normalization_form(&all_words).unwrap_or(NormalizationForm::Nfc);
+    let mut merged_list: Vec<String> = all_words.iter().map(|word| form.normalize(word)).collect();
+    merged_list.sort();
+    merged_list.dedup();
+    merged_list
+}
 
-    for line in file.lines() {
-        match line?.parse() {
-            Ok(l) => vec.push(l),
-            Err(e) => panic!("Error parsing line from file: {:?}", e),
+/// If given comment is a `key: value` header field Phraze knows about, record its value in
+/// `header`. Any other comment is left alone.
+fn read_header_field(comment: &str, header: &mut ListHeader) -> Result<(), String> {
+    let Some((key, value)) = comment.split_once(':') else {
+        return Ok(());
+    };
+    let key = key.trim().to_lowercase();
+    let value = value.trim().to_string();
+    let invalid = |expected: &str| {
+        format!(
+            "Word list header has an invalid {}: '{}'. Expected {}",
+            key, value, expected
+        )
+    };
+    match key.as_ref() {
+        "name" => header.name = Some(value),
+        "expected-count" => {
+            header.expected_count = Some(value.parse().map_err(|_| invalid("a number"))?)
         }
+        "normalization" => header.normalization = Some(value.parse()?),
+        "sha256" => {
+            if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid("64 hexadecimal characters"));
+            }
+            header.sha256 = Some(value.to_lowercase())
+        }
+        "language" => header.language = Some(value),
+        "license" => header.license = Some(value),
+        "uniquely-decodable" => {
+            header.uniquely_decodable = Some(value.parse().map_err(|_| invalid("true or false"))?)
+        }
+        "weighted" => header.weighted = Some(value.parse().map_err(|_| invalid("true or false"))?),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Check that a word list is what its header says it is
+fn check_list_against_header(
+    word_list: &[String],
+    sha256: &str,
+    header: &ListHeader,
+) -> Result<(), String> {
+    if let Some(expected_count) = header.expected_count
+        && expected_count != word_list.len()
+    {
+        return Err(format!(
+            "Word list header says the list has {} words, but it has {}. The file may be incomplete or corrupted",
+            expected_count,
+            word_list.len()
+        ));
+    }
+    if let Some(expected_sha256) = &header.sha256
+        && expected_sha256 != sha256
+    {
+        return Err(format!(
+            "Word list header gives a SHA-256 of {}, but the list's is {}. The file may be incomplete or corrupted",
+            expected_sha256, sha256
+        ));
+    }
+    if let Some(normalization) = header.normalization
+        && let Some(word) = word_list
+            .iter()
+            .find(|word| !normalization.is_normalized(word))
+    {
+        return Err(format!(
+            "Word list header says the list is in {}, but '{}' isn't",
+            normalization, word
+        ));
+    }
+    if header.uniquely_decodable == Some(true) && !is_uniquely_decodable(word_list) {
+        return Err(
+            "Word list header says the list is uniquely decodable, but it isn't".to_string(),
+        );
     }
-    Ok(vec)
+    Ok(())
+}
+
+/// Split a word list file's contents into lines, decompressing them first if the file's name
+/// ends in `.gz`, `.zst` or `.xz`.
+fn read_by_line(file_path: &Path, contents: &[u8]) -> io::Result<Vec<String>> {
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let decompressed = match extension {
+        "gz" => decompress_gzip(contents)?,
+        "zst" => decompress_zstd(contents)?,
+        "xz" => decompress_xz(contents)?,
+        _ => contents.to_vec(),
+    };
+    let text = String::from_utf8(decompressed)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "File isn't valid UTF-8"))?;
+    Ok(text.lines().map(|line| line.to_string()).collect())
+}
+
+#[cfg(feature = "gzip")]
+fn decompress_gzip(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    flate2::read::MultiGzDecoder::new(contents).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    ruzstd::decoding::StreamingDecoder::new(contents)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+        .read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(feature = "xz")]
+fn decompress_xz(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    lzma_rs::xz_decompress(&mut io::BufReader::new(contents), &mut decompressed)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    Ok(decompressed)
+}
+
+// When Phraze is built without support for a compression format, refuse to read files in that
+// format, rather than reading them in as a list of garbled "words"
+#[cfg(not(feature = "gzip"))]
+fn decompress_gzip(_contents: &[u8]) -> io::Result<Vec<u8>> {
+    Err(built_without("gzip"))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_contents: &[u8]) -> io::Result<Vec<u8>> {
+    Err(built_without("zstd"))
+}
+
+#[cfg(not(feature = "xz"))]
+fn decompress_xz(_contents: &[u8]) -> io::Result<Vec<u8>> {
+    Err(built_without("xz"))
+}
+
+#[cfg(not(all(feature = "gzip", feature = "zstd", feature = "xz")))]
+fn built_without(feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "This copy of Phraze was built without the {} feature, so it can't read this compressed file",
+            feature
+        ),
+    )
 }
@@ -0,0 +1,160 @@
+//! A simple model of a handful of keyboard layouts, used to measure how much effort it takes to
+//! type a passphrase. Each key gets an (x, y) position, measured in key widths, and typing effort
+//! is measured as the total distance travelled from key to key.
+use std::fmt;
+use std::str::FromStr;
+
+/// Keyboard layouts Phraze knows about
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum KeyboardLayout {
+    Qwerty,
+    Azerty,
+    Qwertz,
+    Dvorak,
+    Colemak,
+    /// An alphabetical on-screen keyboard, like those found on TVs and video game consoles
+    Alphabetical,
+}
+
+impl KeyboardLayout {
+    /// Rows of keys, top to bottom, along with how far each row is shifted to the right (in key
+    /// widths). Physical layouts are staggered like a standard keyboard.
+    fn rows(self) -> &'static [(&'static str, f64)] {
+        match self {
+            KeyboardLayout::Qwerty => &[
+                ("1234567890-=", 0.0),
+                ("qwertyuiop", 0.5),
+                ("asdfghjkl;", 0.75),
+                ("zxcvbnm,./", 1.25),
+            ],
+            KeyboardLayout::Azerty => &[
+                ("1234567890", 0.0),
+                ("azertyuiop", 0.5),
+                ("qsdfghjklm", 0.75),
+                ("wxcvbn,;:!", 1.25),
+            ],
+            KeyboardLayout::Qwertz => &[
+                ("1234567890", 0.0),
+                ("qwertzuiop", 0.5),
+                ("asdfghjkl", 0.75),
+                ("yxcvbnm,.-", 1.25),
+            ],
+            KeyboardLayout::Dvorak => &[
+                ("1234567890[]", 0.0),
+                ("',.pyfgcrl", 0.5),
+                ("aoeuidhtns", 0.75),
+                (";qjkxbmwvz", 1.25),
+            ],
+            KeyboardLayout::Colemak => &[
+                ("1234567890-=", 0.0),
+                ("qwfpgjluy;", 0.5),
+                ("arstdhneio", 0.75),
+                ("zxcvbkm,./", 1.25),
+            ],
+            KeyboardLayout::Alphabetical => &[
+                ("abcdef", 0.0),
+                ("ghijkl", 0.0),
+                ("mnopqr", 0.0),
+                ("stuvwx", 0.0),
+                ("yz", 0.0),
+            ],
+        }
+    }
+
+    /// Position of the key for the given character, if it's on this layout.
+    /// Uppercase letters are found on the same key as their lowercase versions.
+    pub fn key_position(self, c: char) -> Option<(f64, f64)> {
+        let c = c.to_lowercase().next()?;
+        self.rows()
+            .iter()
+            .enumerate()
+            .find_map(|(row_number, (row, offset))| {
+                row.chars()
+                    .position(|key| key == c)
+                    .map(|column| (column as f64 + offset, row_number as f64))
+            })
+    }
+
+    /// Distance between two keys, in key widths, if both are on this layout
+    pub fn distance_between_keys(self, a: char, b: char) -> Option<f64> {
+        let (ax, ay) = self.key_position(a)?;
+        let (bx, by) = self.key_position(b)?;
+        Some((ax - bx).hypot(ay - by))
+    }
+
+    /// Whether two different keys are right next to each other on this layout
+    pub fn are_adjacent(self, a: char, b: char) -> bool {
+        match self.distance_between_keys(a, b) {
+            Some(distance) => distance > 0.0 && distance <= 1.25,
+            None => false,
+        }
+    }
+
+    /// Total distance travelled, in key widths, typing the given string on this layout.
+    /// Characters that aren't on the layout (like most symbols) are skipped.
+    pub fn travel_distance(self, s: &str) -> f64 {
+        let positions: Vec<(f64, f64)> = s.chars().filter_map(|c| self.key_position(c)).collect();
+        positions
+            .windows(2)
+            .map(|pair| (pair[0].0 - pair[1].0).hypot(pair[0].1 - pair[1].1))
+            .sum()
+    }
+}
+
+impl fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            KeyboardLayout::Qwerty => "QWERTY",
+            KeyboardLayout::Azerty => "AZERTY",
+            KeyboardLayout::Qwertz => "QWERTZ",
+            KeyboardLayout::Dvorak => "Dvorak",
+            KeyboardLayout::Colemak => "Colemak",
+            KeyboardLayout::Alphabetical => "alphabetical",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for KeyboardLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "qwerty" => Ok(KeyboardLayout::Qwerty),
+            "azerty" => Ok(KeyboardLayout::Azerty),
+            "qwertz" => Ok(KeyboardLayout::Qwertz),
+            "dvorak" => Ok(KeyboardLayout::Dvorak),
+            "colemak" => Ok(KeyboardLayout::Colemak),
+            "alpha" | "alphabetical" => Ok(KeyboardLayout::Alphabetical),
+            _ => Err(format!(
+                "Inputted keyboard layout '{}' isn't one Phraze knows about. Options are qwerty, azerty, qwertz, dvorak, colemak and alpha",
+                s
+            )),
+        }
+    }
+}
+
+#[test]
+fn can_find_adjacent_keys() {
+    assert!(KeyboardLayout::Qwerty.are_adjacent('s', 'd'));
+    assert!(KeyboardLayout::Qwerty.are_adjacent('s', 'w'));
+    assert!(!KeyboardLayout::Qwerty.are_adjacent('r', 'u'));
+    assert!(!KeyboardLayout::Qwerty.are_adjacent('s', 's'));
+    assert!(KeyboardLayout::Azerty.are_adjacent('a', 'z'));
+    assert!(KeyboardLayout::Alphabetical.are_adjacent('a', 'g'));
+    assert!(!KeyboardLayout::Alphabetical.are_adjacent('f', 'g'));
+}
+
+#[test]
+fn can_measure_travel_distance() {
+    assert_eq!(KeyboardLayout::Qwerty.travel_distance("asdf"), 3.0);
+    assert_eq!(KeyboardLayout::Qwerty.travel_distance("ASDF"), 3.0);
+    // Symbols that aren't on the layout are skipped
+    assert_eq!(KeyboardLayout::Qwerty.travel_distance("as&df"), 3.0);
+    assert_eq!(KeyboardLayout::Alphabetical.travel_distance("agm"), 2.0);
+    // "aoeu" is the home row on Dvorak, but not on QWERTY
+    assert!(
+        KeyboardLayout::Dvorak.travel_distance("aoeu")
+            < KeyboardLayout::Qwerty.travel_distance("aoeu")
+    );
+}
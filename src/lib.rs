@@ -1,128 +1,545 @@
+pub mod alphabets;
+pub mod casing;
+pub mod charset;
+pub mod check;
+pub mod checksum;
+#[cfg(feature = "cli")]
 pub mod cli;
+pub mod decompose;
 pub mod file_reader;
+pub mod keyboard_layouts;
+pub mod list_checks;
+pub mod list_choice;
+pub mod list_info;
+pub mod mnemonic_encoding;
+pub mod pinned_lists;
+pub mod presets;
+pub mod pseudo_words;
+pub mod recover;
 pub mod separators;
+pub mod templates;
 pub mod unicode_normalization_check;
+pub mod user_lists;
 
-use crate::cli::ListChoice;
-use crate::separators::make_separator;
+use crate::casing::title_case;
+use crate::charset::Charset;
+use crate::checksum::checksum_word_index;
+use crate::list_choice::{ListChoice, ListMetadata};
+use crate::pseudo_words::PseudoWords;
+use crate::separators::{make_separator, make_separator_in_charset};
+use crate::unicode_normalization_check::detect_normalization_form;
 use include_lines::include_lines;
 // use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::distr::weighted::WeightedIndex;
 use rand::prelude::*;
 use rand::rng;
-use rand::seq::IndexedRandom;
 
 /// Given user's inputs, figure out how many words the generated passphrase will need. If user
 /// specified an exact `number_of_words`, just return that `number_of_words`. If user is using a
 /// strength_count, do the necessary math. If user specified a `minimum_entropy`, we need to do
-/// some math to figure out how many words will clear that minimum.
+/// some math to figure out how many words will clear that minimum. Returns an error if the list
+/// is too short to make passphrases from (see `check_list_length`).
 pub fn calculate_number_words_needed(
     number_of_words: Option<usize>,
     minimum_entropy: Option<usize>,
     strength_count: u8,
     list_length: usize,
-) -> usize {
+) -> Result<usize, String> {
+    check_list_length(list_length)?;
+    calculate_number_words_needed_from_bits_per_word(
+        number_of_words,
+        minimum_entropy,
+        strength_count,
+        (list_length as f64).log2(),
+    )
+}
+
+/// Same as `calculate_number_words_needed`, but for lists where each word adds the given number
+/// of bits of entropy. For a list where every word is equally likely, that's log2 of the list's
+/// length. For a weighted list, it should be the list's min-entropy per word (see `list_info`),
+/// so that passphrases are sized by the most likely words, not the average ones. Returns an error
+/// if words add no entropy, since no number of them would make a passphrase worth having.
+pub fn calculate_number_words_needed_from_bits_per_word(
+    number_of_words: Option<usize>,
+    minimum_entropy: Option<usize>,
+    strength_count: u8,
+    bits_per_word: f64,
+) -> Result<usize, String> {
+    check_bits_per_word(bits_per_word)?;
     // If a number of words was requested exactly by the user, use that
+    if let Some(number_of_words) = number_of_words {
+        return Ok(number_of_words);
+    }
+    // convert the entropy user wants into number of words, using entropy per word
+    Ok(words_needed_for_entropy(
+        minimum_entropy_wanted(minimum_entropy, strength_count),
+        bits_per_word,
+    ))
+}
+
+/// Same as `calculate_number_words_needed`, but for passphrases made from a template, where
+/// each word is picked from its own list (see the `templates` module). `slot_sizes` gives the
+/// length of each slot's list, in the template's order. Once every slot has been used, the
+/// template starts over, so a passphrase can have more words than the template has slots.
+pub fn calculate_number_words_needed_per_slot(
+    number_of_words: Option<usize>,
+    minimum_entropy: Option<usize>,
+    strength_count: u8,
+    slot_sizes: &[usize],
+) -> usize {
     if let Some(number_of_words) = number_of_words {
         return number_of_words;
     }
+    // A template whose slots add no entropy would never get there
+    if entropy_of_slots(slot_sizes, slot_sizes.len()) == 0.0 {
+        return slot_sizes.len();
+    }
+    let minimum_entropy = minimum_entropy_wanted(minimum_entropy, strength_count) as f64;
+    let mut number_of_words = 0;
+    while entropy_of_slots(slot_sizes, number_of_words) < minimum_entropy {
+        number_of_words += 1;
+    }
+    number_of_words
+}
+
+/// Entropy, in bits, of a passphrase with the given number of words made from a template with
+/// the given slot sizes: the sum of log2 of the size of each word's slot.
+pub fn entropy_of_slots(slot_sizes: &[usize], number_of_words: usize) -> f64 {
+    slot_sizes
+        .iter()
+        .cycle()
+        .take(number_of_words)
+        .map(|slot_size| (*slot_size as f64).log2())
+        .sum()
+}
 
-    const DEFAULT_MINIMUM_ENTROPY: usize = 80;
+/// How much entropy, in bits, user wants their passphrases to have
+fn minimum_entropy_wanted(minimum_entropy: Option<usize>, strength_count: u8) -> usize {
     // If they used the strength count option, do some math to calculate what minimum_entropy
-    // we should give them, then convert that into number of bits of entropy.
+    // we should give them.
     if strength_count > 0 {
         // Use number of Ss to calculate minimum_entropy in bits
-        let minimum_entropy = DEFAULT_MINIMUM_ENTROPY + (strength_count as usize) * 20;
-        // convert this into number of words, using list length
-        return convert_minimum_entropy_to_number_of_words(minimum_entropy, list_length);
+        return DEFAULT_MINIMUM_ENTROPY + (strength_count as usize) * 20;
     }
-    // If we made it here, that means either the user requested a specific minimum_entropy in bits,
-    // or they entered no relevant settings. Let's handle both cases with a match statement.
-    match minimum_entropy {
-        // If a minimum_entropy is set by user, use that.
-        Some(minimum_entropy) => {
-            convert_minimum_entropy_to_number_of_words(minimum_entropy, list_length)
-        }
-        // If none of these 3 settings were given, use the DEFAULT_MINIMUM_ENTROPY
-        None => convert_minimum_entropy_to_number_of_words(DEFAULT_MINIMUM_ENTROPY, list_length),
+    // If we made it here, that means either the user requested a specific minimum_entropy in
+    // bits, or they entered no relevant settings. If none of these settings were given, use the
+    // DEFAULT_MINIMUM_ENTROPY.
+    minimum_entropy.unwrap_or(DEFAULT_MINIMUM_ENTROPY)
+}
+
+/// Phraze's default minimum entropy, in bits, when user doesn't ask for anything else
+pub const DEFAULT_MINIMUM_ENTROPY: usize = 80;
+
+/// Fewest words a list can have for Phraze to make passphrases from it. A list of one word gives
+/// passphrases no entropy at all (and would have us divide by log2(1) = 0 when figuring out how
+/// many words a passphrase needs).
+pub const MINIMUM_LIST_LENGTH: usize = 2;
+
+/// Passphrases with less entropy than this, in bits, are weak enough to warn the user about
+pub const LOW_ENTROPY_WARNING_THRESHOLD: f64 = 50.0;
+
+/// Check that a word list has enough words to make passphrases from.
+pub fn check_list_length(list_length: usize) -> Result<(), String> {
+    if list_length < MINIMUM_LIST_LENGTH {
+        return Err(format!(
+            "Word list has {} word{}, but Phraze needs at least {} different words to make a passphrase",
+            list_length,
+            if list_length == 1 { "" } else { "s" },
+            MINIMUM_LIST_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// Check that each word picked from a list adds some entropy. Otherwise we'd divide by zero (or
+/// worse) when figuring out how many words a passphrase needs.
+fn check_bits_per_word(bits_per_word: f64) -> Result<(), String> {
+    if !(bits_per_word > 0.0 && bits_per_word.is_finite()) {
+        return Err(format!(
+            "Each word from this list adds {:.2} bits of entropy, so no number of words would make a strong passphrase",
+            bits_per_word
+        ));
     }
+    Ok(())
 }
 
 /// Calculate the number of words needed to meet a desired
 /// minimum entropy, given the length of the word list we're using.
+/// The list must have at least `MINIMUM_LIST_LENGTH` words (see `check_list_length`), or this
+/// returns an error.
 pub fn convert_minimum_entropy_to_number_of_words(
     minimum_entropy: usize,
     list_length: usize,
-) -> usize {
+) -> Result<usize, String> {
+    check_list_length(list_length)?;
     let entropy_per_word_from_this_list = (list_length as f64).log2();
-    (minimum_entropy as f64 / entropy_per_word_from_this_list).ceil() as usize
+    Ok(words_needed_for_entropy(
+        minimum_entropy,
+        entropy_per_word_from_this_list,
+    ))
+}
+
+fn words_needed_for_entropy(minimum_entropy: usize, bits_per_word: f64) -> usize {
+    (minimum_entropy as f64 / bits_per_word).ceil() as usize
+}
+
+/// Fewest words, but no fewer than `number_of_words`, that make every passphrase at least
+/// `minimum_length` characters long, even one made of nothing but the shortest word on the list.
+/// Adding words only ever adds entropy, so this is safe to do after working out how many words
+/// the entropy calls for.
+pub fn words_for_minimum_length(
+    number_of_words: usize,
+    shortest_word_length: usize,
+    separator_length: usize,
+    minimum_length: usize,
+) -> usize {
+    let shortest_passphrase_length =
+        |n: usize| n * shortest_word_length + n.saturating_sub(1) * separator_length;
+    if shortest_word_length == 0 && separator_length == 0 {
+        return number_of_words;
+    }
+    let mut number_of_words = number_of_words;
+    while shortest_passphrase_length(number_of_words) < minimum_length {
+        number_of_words += 1;
+    }
+    number_of_words
+}
+
+/// Shorten a word list so that every passphrase made from it fits in `maximum_length`
+/// characters, no matter which words get picked. Returns the shortened list along with how many
+/// words a passphrase needs, which `words_needed` works out from a list.
+///
+/// Rather than making passphrases and throwing out any that run long (which would make some
+/// words less likely than others, and our entropy estimates wrong), we drop the words that are
+/// too long to ever fit. Dropping words can mean a passphrase needs more words, and so shorter
+/// ones, so we keep going until the list stops shrinking.
+pub fn words_that_fit<'a>(
+    word_list: &[&'a str],
+    words_needed: impl Fn(&[&str]) -> Result<usize, String>,
+    separator_length: usize,
+    maximum_length: usize,
+) -> Result<(Vec<&'a str>, usize), String> {
+    let mut fitting_words = word_list.to_vec();
+    loop {
+        let number_of_words = words_needed(&fitting_words)?;
+        let separators_length = number_of_words.saturating_sub(1) * separator_length;
+        let longest_word_allowed = maximum_length
+            .checked_sub(separators_length)
+            .map(|room_for_words| room_for_words / number_of_words.max(1))
+            .unwrap_or(0);
+        let shorter_words: Vec<&str> = fitting_words
+            .iter()
+            .filter(|word| word.chars().count() <= longest_word_allowed)
+            .copied()
+            .collect();
+        if shorter_words.len() == fitting_words.len() {
+            return Ok((fitting_words, number_of_words));
+        }
+        if shorter_words.len() < MINIMUM_LIST_LENGTH {
+            return Err(format!(
+                "Can't fit a passphrase of {} words into {} characters. Try a list with shorter words, or a higher maximum length",
+                number_of_words, maximum_length
+            ));
+        }
+        fitting_words = shorter_words;
+    }
 }
 
 /// Take enum of `list_choice` and use the `include_lines!` macro (from crate)
 /// to read-in the correct word list.
 pub fn fetch_list(list_choice: ListChoice) -> &'static [&'static str] {
     match list_choice {
+        #[cfg(feature = "list-long")]
         ListChoice::Long => &include_lines!("word-lists/orchard-street-long.txt"),
+        #[cfg(feature = "list-medium")]
         ListChoice::Medium => &include_lines!("word-lists/orchard-street-medium.txt"),
+        #[cfg(feature = "list-qwerty")]
         ListChoice::Qwerty => &include_lines!("word-lists/orchard-street-qwerty.txt"),
+        #[cfg(feature = "list-alpha")]
         ListChoice::Alpha => &include_lines!("word-lists/orchard-street-alpha.txt"),
+        #[cfg(feature = "list-eff")]
         ListChoice::Eff => &include_lines!("word-lists/eff-long.txt"),
+        #[cfg(feature = "list-eff-short")]
         ListChoice::Effshort => &include_lines!("word-lists/eff-short-1.txt"),
+        #[cfg(feature = "list-mnemonicode")]
         ListChoice::Mnemonicode => &include_lines!("word-lists/mnemonicode.txt"),
     }
 }
 
+// Defines BUNDLED_LIST_METADATA: the file name and metadata of every list in word-lists/, which
+// build.rs checks and measures before Phraze is built
+include!(concat!(env!("OUT_DIR"), "/list_metadata.rs"));
+
+/// Look up the word count and bits per word of a built-in list, as measured by build.rs
+pub fn list_metadata(list_choice: ListChoice) -> ListMetadata {
+    BUNDLED_LIST_METADATA
+        .iter()
+        .find(|(file_name, _)| *file_name == list_choice.file_name())
+        .map(|(_, metadata)| *metadata)
+        .expect("build.rs measures every list in word-lists/")
+}
+
 /// Actually generate the passphrase, given a couple neccessary parameters.
 /// This function uses some Rust magic to be able to accept a word list as
 /// either a `&[&str]` (if the users uses a built-in word lists) or as a
 /// `&[String]` (if user provides a file as word list).
+/// If `checksum_word` is true, an extra word, derived from the others, is added to the end of the
+/// passphrase (see the `checksum` module). Returns an error if the list is too short to make
+/// passphrases from (see `check_list_length`).
 pub fn generate_a_passphrase<T: AsRef<str> + std::fmt::Display>(
     number_of_words_to_put_in_passphrase: usize,
     separator: &str,
     title_case: bool,
+    checksum_word: bool,
     list: &[T], // We accept either type by using `T`!
-) -> String {
+) -> Result<String, String> {
     let mut rng = rng(); // How we make a RNG using rand v0.9.0
+    generate_a_passphrase_with_rng(
+        &mut rng,
+        number_of_words_to_put_in_passphrase,
+        separator,
+        title_case,
+        checksum_word,
+        None,
+        list,
+    )
+}
 
+/// Same as `generate_a_passphrase`, but uses the given random number generator rather than the
+/// thread-local one. This lets tests use a seeded RNG, so that their results can be reproduced.
+/// If a `charset` is given, random separators are only picked from characters in it. (Leaving out
+/// words that aren't in the charset is up to the caller.)
+pub fn generate_a_passphrase_with_rng<T: AsRef<str> + std::fmt::Display>(
+    rng: &mut impl Rng,
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    checksum_word: bool,
+    charset: Option<&Charset>,
+    list: &[T],
+) -> Result<String, String> {
+    check_list_length(list.len())?;
+    // First, pick the words we'll use, by their index on the list
+    let word_indexes: Vec<usize> = (0..number_of_words_to_put_in_passphrase)
+        .map(|_| get_random_index(rng, list))
+        .collect::<Result<_, _>>()?;
+    Ok(assemble_passphrase(
+        rng,
+        word_indexes,
+        separator,
+        title_case,
+        checksum_word,
+        charset,
+        list,
+    ))
+}
+
+/// Same as `generate_a_passphrase_with_rng`, but for a weighted list, where some words are more
+/// likely to be picked than others. `weights` gives the weight of each word on the list.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_a_weighted_passphrase_with_rng<T: AsRef<str> + std::fmt::Display>(
+    rng: &mut impl Rng,
+    weights: &WeightedIndex<f64>,
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    checksum_word: bool,
+    charset: Option<&Charset>,
+    list: &[T],
+) -> Result<String, String> {
+    check_list_length(list.len())?;
+    let word_indexes: Vec<usize> = (0..number_of_words_to_put_in_passphrase)
+        .map(|_| weights.sample(rng))
+        .collect();
+    Ok(assemble_passphrase(
+        rng,
+        word_indexes,
+        separator,
+        title_case,
+        checksum_word,
+        charset,
+        list,
+    ))
+}
+
+/// Generate a passphrase from a template, where each word is picked from the list for its slot.
+/// `slot_lists` has the list for each slot of the template, in order, and the template starts
+/// over if `number_of_words_to_put_in_passphrase` is more than the number of slots. Returns an
+/// error if a slot's list is empty.
+pub fn generate_a_passphrase_from_template_with_rng<T: AsRef<str>>(
+    rng: &mut impl Rng,
+    slot_lists: &[&[T]],
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    charset: Option<&Charset>,
+) -> Result<String, String> {
+    let words: Vec<&str> = slot_lists
+        .iter()
+        .cycle()
+        .take(number_of_words_to_put_in_passphrase)
+        .map(|slot_list| Ok(slot_list[get_random_index(rng, slot_list)?].as_ref()))
+        .collect::<Result<_, String>>()?;
+    Ok(join_words(rng, &words, separator, charset))
+}
+
+/// Generate a passphrase of made-up, pronounceable pseudo-words (see the `pseudo_words` module).
+/// Every letter of every pseudo-word is picked uniformly from the letters for its spot. Returns
+/// an error if there are no letters for a spot, as when none of them are in a charset.
+pub fn generate_a_pseudo_word_passphrase_with_rng(
+    rng: &mut impl Rng,
+    pseudo_words: &PseudoWords,
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    charset: Option<&Charset>,
+) -> Result<String, String> {
+    let words: Vec<String> = (0..number_of_words_to_put_in_passphrase)
+        .map(|_| {
+            let pseudo_word: String = pseudo_words
+                .pattern
+                .letters
+                .iter()
+                .map(|letter| {
+                    let letters = pseudo_words.letters_for(*letter);
+                    Ok(letters[get_random_index(rng, letters)?])
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(if title_case {
+                make_title_case(&pseudo_word)
+            } else {
+                pseudo_word
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(join_words(rng, &words, separator, charset))
+}
+
+/// Put together a passphrase out of the words at the given indexes of the list
+fn assemble_passphrase<T: AsRef<str> + std::fmt::Display>(
+    rng: &mut impl Rng,
+    mut word_indexes: Vec<usize>,
+    separator: &str,
+    title_case: bool,
+    checksum_word: bool,
+    charset: Option<&Charset>,
+    list: &[T],
+) -> String {
+    if checksum_word {
+        word_indexes.push(checksum_word_index(&word_indexes, list.len()));
+    }
+
+    let words: Vec<String> = word_indexes
+        .iter()
+        .map(|word_index| {
+            // Check if we're doing title_case
+            if title_case {
+                make_title_case(list[*word_index].as_ref())
+            } else {
+                list[*word_index].to_string()
+            }
+        })
+        .collect();
+    join_words(rng, &words, separator, charset)
+}
+
+/// Join the words of a passphrase together with separators
+fn join_words<T: AsRef<str>>(
+    rng: &mut impl Rng,
+    words: &[T],
+    separator: &str,
+    charset: Option<&Charset>,
+) -> String {
     // Create a blank String to put words into to create our passphrase
     let mut passphrase = String::new();
-    for i in 0..number_of_words_to_put_in_passphrase {
-        // Check if we're doing title_case
-        let random_word = if title_case {
-            make_title_case(&get_random_element(&mut rng, list))
-        } else {
-            get_random_element(&mut rng, list)
-        };
+    for (i, word) in words.iter().enumerate() {
         // Add this word to our passphrase
-        passphrase += &random_word;
+        passphrase += word.as_ref();
         // Add a separator
-        if i != number_of_words_to_put_in_passphrase - 1 {
-            passphrase += &make_separator(&mut rng, separator);
+        if i != words.len() - 1 {
+            passphrase += &match charset {
+                Some(charset) => make_separator_in_charset(rng, separator, charset),
+                None => make_separator(rng, separator),
+            };
         }
     }
-    passphrase.to_string()
-}
-
-/// Given an array of words, pick a random element. Then make  
-/// the selected word a `String` for simplicity's sake.
-fn get_random_element<T>(rng: &mut impl Rng, word_list: &[T]) -> String
-where
-    T: std::fmt::Display + AsRef<str>,
-{
-    match word_list.choose(rng) {
-        Some(word) => word.to_string(),
-        None => panic!("Couldn't pick a random word"),
+    passphrase
+}
+
+/// Given an array of words, pick the index of a random element.
+fn get_random_index<T>(rng: &mut impl Rng, word_list: &[T]) -> Result<usize, String> {
+    if word_list.is_empty() {
+        return Err("Couldn't pick a random word from an empty list".to_string());
     }
+    Ok(rng.random_range(0..word_list.len()))
+}
+
+/// Make given string slice `s` all lowercase, then make its first letter uppercase, keeping it
+/// in the same Unicode normalization form. See the `casing` module for locale-specific rules.
+pub fn make_title_case(s: &str) -> String {
+    title_case(s, None, detect_normalization_form(&[s]))
+}
+
+#[test]
+fn can_reject_lists_too_short_to_make_passphrases_from() {
+    assert!(check_list_length(0).is_err());
+    assert!(check_list_length(1).is_err());
+    assert!(check_list_length(2).is_ok());
 }
 
-/// Make given string slice `s` all lowercase, then make first character uppercase
-fn make_title_case(s: &str) -> String {
-    // First, make entire word lowercase
-    let s = s.to_lowercase();
-    let mut c = s.chars();
-    match c.next() {
-        None => String::new(),
-        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+#[test]
+fn can_reject_lists_of_zero_or_one_words_when_sizing_passphrases() {
+    for list_length in [0, 1] {
+        assert!(convert_minimum_entropy_to_number_of_words(80, list_length).is_err());
+        assert!(calculate_number_words_needed(None, None, 0, list_length).is_err());
+        assert!(calculate_number_words_needed(Some(5), None, 0, list_length).is_err());
     }
+    assert_eq!(convert_minimum_entropy_to_number_of_words(80, 2), Ok(80));
+    // A weighted list whose most likely word is certain to be picked adds no entropy
+    assert!(calculate_number_words_needed_from_bits_per_word(None, None, 0, 0.0).is_err());
+    assert!(calculate_number_words_needed_from_bits_per_word(None, None, 0, f64::NAN).is_err());
+}
+
+#[test]
+fn can_reject_lists_of_zero_or_one_words_when_generating_passphrases() {
+    let empty: [&str; 0] = [];
+    assert!(generate_a_passphrase(3, "-", false, false, &empty).is_err());
+    assert!(generate_a_passphrase(3, "-", false, false, &["solo"]).is_err());
+    assert!(generate_a_passphrase(3, "-", false, false, &["one", "two"]).is_ok());
+    let mut rng = rng();
+    let slot_lists: [&[&str]; 2] = [&["big"], &empty];
+    assert!(
+        generate_a_passphrase_from_template_with_rng(&mut rng, &slot_lists, 2, "-", None).is_err()
+    );
+    let mut pseudo_words = PseudoWords::new("cv".parse().unwrap());
+    pseudo_words.keep_letters(|c| c != 'a' && c != 'e' && c != 'i' && c != 'o' && c != 'u');
+    assert!(
+        generate_a_pseudo_word_passphrase_with_rng(&mut rng, &pseudo_words, 2, "-", false, None)
+            .is_err()
+    );
+}
+
+#[test]
+fn can_add_words_so_passphrases_reach_a_minimum_length() {
+    // 2 words of at least 3 characters, with a separator, can be as short as 7 characters
+    assert_eq!(words_for_minimum_length(2, 3, 1, 7), 2);
+    assert_eq!(words_for_minimum_length(2, 3, 1, 8), 3);
+    assert_eq!(words_for_minimum_length(1, 1, 0, 8), 8);
+    assert_eq!(words_for_minimum_length(5, 1, 0, 0), 5);
+}
+
+#[test]
+fn can_shorten_a_list_so_passphrases_fit_a_maximum_length() {
+    let list = ["a", "bb", "cc", "ddd", "eeee", "ffffffff"];
+    // 3 words, 2 separators: 10 characters leaves room for words of 2 characters
+    let (fitting_words, number_of_words) = words_that_fit(&list, |_| Ok(3), 1, 10).unwrap();
+    assert_eq!(fitting_words, vec!["a", "bb", "cc"]);
+    assert_eq!(number_of_words, 3);
+    // Shorter lists need more words
+    let words_needed = |list: &[&str]| Ok(if list.len() > 5 { 2 } else { 3 });
+    let (fitting_words, number_of_words) = words_that_fit(&list, words_needed, 0, 8).unwrap();
+    assert_eq!(fitting_words, vec!["a", "bb", "cc"]);
+    assert_eq!(number_of_words, 3);
+    assert!(words_that_fit(&list, |_| Ok(3), 1, 4).is_err());
 }
 
 #[test]
@@ -137,20 +554,31 @@ fn can_make_word_title_case() {
     assert_eq!(make_title_case(test_word), "Alpha".to_string());
 }
 
-/// Print the calculated (estimated) entropy of a passphrase, based on three variables
-pub fn print_entropy(number_of_words: usize, list_length: usize, n_passphrases: usize) {
-    let passphrase_entropy = (list_length as f64).log2() * number_of_words as f64;
+/// Print the estimated entropy of a passphrase (or password): `number_of_items` items, each
+/// worth `bits_per_item` bits. `noun` is what's been generated ("passphrase" or "password"),
+/// `unit` is what it's made of ("words", "characters", "pseudo-words") and `source` is where those
+/// items came from, like "from a list of 7776 words".
+pub fn print_entropy(
+    noun: &str,
+    number_of_items: usize,
+    unit: &str,
+    bits_per_item: f64,
+    source: &str,
+    n_passphrases: usize,
+) {
     // Depending on how many different passphrases the user wants printed, change the printed text
     // accordingly
-    if n_passphrases == 1 {
-        eprintln!(
-            "Passphrase has an estimated {:.2} bits of entropy ({} words from a list of {} words)",
-            passphrase_entropy, number_of_words, list_length,
-        );
+    let subject = if n_passphrases == 1 {
+        make_title_case(noun)
     } else {
-        eprintln!(
-            "Each passphrase has an estimated {:.2} bits of entropy ({} words from a list of {} words)",
-            passphrase_entropy, number_of_words, list_length
-        );
-    }
+        format!("Each {}", noun)
+    };
+    eprintln!(
+        "{} has an estimated {:.2} bits of entropy ({} {} {})",
+        subject,
+        bits_per_item * number_of_items as f64,
+        number_of_items,
+        unit,
+        source
+    );
 }
@@ -0,0 +1,137 @@
+//! Checks that a word list has the properties Phraze promises for its built-in lists: no blank
+//! words, no stray whitespace, no duplicates, sorted, uniform Unicode normalization, and uniquely
+//! decodable. build.rs runs these checks on every list in `word-lists/`, and refuses to build
+//! Phraze if any of them fail.
+//!
+//! Since build.rs includes this file directly, it can only use the standard library,
+//! unicode-normalization, and the `unicode_normalization_check` module.
+use crate::unicode_normalization_check::uniform_unicode_normalization;
+use std::collections::HashSet;
+
+/// Find everything wrong with the given word list, described in plain English. An empty `Vec`
+/// means the list passed every check.
+pub fn problems_with_list(list: &[String]) -> Vec<String> {
+    let mut problems = vec![];
+    for (line_number, word) in list.iter().enumerate().map(|(i, word)| (i + 1, word)) {
+        if word.trim().is_empty() {
+            problems.push(format!("line {} is blank", line_number));
+        } else if word.trim() != word {
+            problems.push(format!(
+                "line {} ('{}') has whitespace at its start or end",
+                line_number, word
+            ));
+        }
+    }
+    let mut seen = HashSet::new();
+    for word in list {
+        if !seen.insert(word) {
+            problems.push(format!("'{}' appears more than once", word));
+        }
+    }
+    if let Some(pair) = list.windows(2).find(|pair| pair[0] > pair[1]) {
+        problems.push(format!(
+            "not sorted: '{}' comes before '{}'",
+            pair[0], pair[1]
+        ));
+    }
+    if !list.is_empty() && !uniform_unicode_normalization(list) {
+        problems.push("uses more than one Unicode normalization".to_string());
+    }
+    if !is_uniquely_decodable(list) {
+        problems.push("is not uniquely decodable".to_string());
+    }
+    problems
+}
+
+/// Use the Sardinas-Patterson algorithm to check whether the list is uniquely decodable: that is,
+/// whether every string of words from the list, joined without a separator, can only be split back
+/// into words one way.
+///
+/// The algorithm collects "dangling suffixes": what's left over when one word (or leftover) is a
+/// prefix of another. The list is uniquely decodable unless some leftover is itself a word.
+pub fn is_uniquely_decodable<T: AsRef<str>>(list: &[T]) -> bool {
+    let words: HashSet<&str> = list.iter().map(|word| word.as_ref()).collect();
+    let mut sorted_words: Vec<&str> = words.iter().copied().collect();
+    sorted_words.sort_unstable();
+
+    // First round: leftovers when one word is a prefix of another word
+    let mut new_suffixes: Vec<String> = sorted_words
+        .iter()
+        .flat_map(|word| suffixes_after_words_on_list(word, &words))
+        .collect();
+    let mut seen_suffixes: HashSet<String> = HashSet::new();
+    while let Some(suffix) = new_suffixes.pop() {
+        if words.contains(suffix.as_str()) {
+            return false;
+        }
+        if !seen_suffixes.insert(suffix.clone()) {
+            continue;
+        }
+        // A word on the list that's a prefix of this leftover...
+        new_suffixes.extend(suffixes_after_words_on_list(&suffix, &words));
+        // ...or a word on the list that this leftover is a prefix of
+        new_suffixes.extend(
+            words_starting_with(&suffix, &sorted_words)
+                .map(|word| word[suffix.len()..].to_string()),
+        );
+    }
+    true
+}
+
+/// For every word on the list that is a proper prefix of `s`, whatever's left of `s` after it
+fn suffixes_after_words_on_list(s: &str, words: &HashSet<&str>) -> Vec<String> {
+    s.char_indices()
+        .skip(1)
+        .filter(|(i, _)| words.contains(&s[..*i]))
+        .map(|(i, _)| s[i..].to_string())
+        .collect()
+}
+
+/// Every word on the (sorted) list that starts with, but isn't equal to, `prefix`
+fn words_starting_with<'a>(
+    prefix: &'a str,
+    sorted_words: &'a [&'a str],
+) -> impl Iterator<Item = &'a str> + 'a {
+    let start = sorted_words.partition_point(|word| *word < prefix);
+    sorted_words[start..]
+        .iter()
+        .take_while(move |word| word.starts_with(prefix))
+        .filter(move |word| **word != prefix)
+        .copied()
+}
+
+#[test]
+fn can_tell_whether_a_list_is_uniquely_decodable() {
+    // Prefix-free lists are always uniquely decodable
+    assert!(is_uniquely_decodable(&["apple", "banana", "cherry"]));
+    // "a" is a prefix of "ab", but any string of these can still only be split one way
+    assert!(is_uniquely_decodable(&["a", "ab", "bb"]));
+    // "ab" + "a" and "a" + "ba" are both "aba"
+    assert!(!is_uniquely_decodable(&["a", "ab", "ba"]));
+    // "neighbor" + "hood" and "neighborhood"
+    assert!(!is_uniquely_decodable(&[
+        "neighbor",
+        "hood",
+        "neighborhood"
+    ]));
+}
+
+#[test]
+fn can_find_problems_with_a_word_list() {
+    let list = |words: &[&str]| -> Vec<String> { words.iter().map(|w| w.to_string()).collect() };
+    assert!(problems_with_list(&list(&["apple", "banana", "cherry"])).is_empty());
+    assert_eq!(problems_with_list(&list(&["apple", "", "cherry"])).len(), 2);
+    assert_eq!(
+        problems_with_list(&list(&["apple", "cherry", "banana"])),
+        vec!["not sorted: 'cherry' comes before 'banana'"]
+    );
+    let problems = problems_with_list(&list(&["apple", "apple", "banana "]));
+    assert!(problems.contains(&"'apple' appears more than once".to_string()));
+    assert!(
+        problems.contains(&"line 3 ('banana ') has whitespace at its start or end".to_string())
+    );
+    assert_eq!(
+        problems_with_list(&list(&["a", "ab", "ba"])),
+        vec!["is not uniquely decodable"]
+    );
+}
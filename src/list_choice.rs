@@ -0,0 +1,193 @@
+//! The built-in word lists Phraze can use. This module doesn't depend on clap, so that the
+//! library can be used without the command-line interface.
+use std::fmt;
+use std::str::FromStr;
+
+/// This enum, `ListChoice`, represents all of the "built-in" word lists that Phraze can use.
+/// Each list is only built in if its cargo feature is enabled.
+///
+/// With the `serde` feature enabled, lists are serialized as their canonical names (like
+/// "orchard-street-medium"), and can be deserialized from either their names or one-letter codes.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ListChoice {
+    #[cfg(feature = "list-long")]
+    #[cfg_attr(feature = "serde", serde(rename = "orchard-street-long", alias = "l"))]
+    Long,
+    #[cfg(feature = "list-medium")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "orchard-street-medium", alias = "m")
+    )]
+    Medium,
+    #[cfg(feature = "list-eff")]
+    #[cfg_attr(feature = "serde", serde(rename = "eff-long", alias = "e"))]
+    Eff,
+    #[cfg(feature = "list-mnemonicode")]
+    #[cfg_attr(feature = "serde", serde(rename = "mnemonicode", alias = "n"))]
+    Mnemonicode,
+    #[cfg(feature = "list-eff-short")]
+    #[cfg_attr(feature = "serde", serde(rename = "eff-short", alias = "s"))]
+    Effshort,
+    #[cfg(feature = "list-qwerty")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "orchard-street-qwerty", alias = "q")
+    )]
+    Qwerty,
+    #[cfg(feature = "list-alpha")]
+    #[cfg_attr(feature = "serde", serde(rename = "orchard-street-alpha", alias = "a"))]
+    Alpha,
+}
+
+#[cfg(not(any(
+    feature = "list-long",
+    feature = "list-medium",
+    feature = "list-eff",
+    feature = "list-mnemonicode",
+    feature = "list-eff-short",
+    feature = "list-qwerty",
+    feature = "list-alpha"
+)))]
+compile_error!(
+    "Phraze needs at least one built-in word list. Enable at least one of the list-* features, for example: `cargo build --no-default-features --features list-medium`"
+);
+
+impl ListChoice {
+    /// Every built-in list, in the order they're shown in help text. The first is the default.
+    pub const ALL: &'static [ListChoice] = &[
+        #[cfg(feature = "list-medium")]
+        ListChoice::Medium,
+        #[cfg(feature = "list-long")]
+        ListChoice::Long,
+        #[cfg(feature = "list-eff")]
+        ListChoice::Eff,
+        #[cfg(feature = "list-mnemonicode")]
+        ListChoice::Mnemonicode,
+        #[cfg(feature = "list-eff-short")]
+        ListChoice::Effshort,
+        #[cfg(feature = "list-qwerty")]
+        ListChoice::Qwerty,
+        #[cfg(feature = "list-alpha")]
+        ListChoice::Alpha,
+    ];
+
+    /// Canonical name of the list, like "orchard-street-medium". This is what `Display` prints.
+    pub const fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "list-long")]
+            ListChoice::Long => "orchard-street-long",
+            #[cfg(feature = "list-medium")]
+            ListChoice::Medium => "orchard-street-medium",
+            #[cfg(feature = "list-eff")]
+            ListChoice::Eff => "eff-long",
+            #[cfg(feature = "list-mnemonicode")]
+            ListChoice::Mnemonicode => "mnemonicode",
+            #[cfg(feature = "list-eff-short")]
+            ListChoice::Effshort => "eff-short",
+            #[cfg(feature = "list-qwerty")]
+            ListChoice::Qwerty => "orchard-street-qwerty",
+            #[cfg(feature = "list-alpha")]
+            ListChoice::Alpha => "orchard-street-alpha",
+        }
+    }
+
+    /// Name of the file in `word-lists/` that this list is read from
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "list-long")]
+            ListChoice::Long => "orchard-street-long.txt",
+            #[cfg(feature = "list-medium")]
+            ListChoice::Medium => "orchard-street-medium.txt",
+            #[cfg(feature = "list-eff")]
+            ListChoice::Eff => "eff-long.txt",
+            #[cfg(feature = "list-mnemonicode")]
+            ListChoice::Mnemonicode => "mnemonicode.txt",
+            #[cfg(feature = "list-eff-short")]
+            ListChoice::Effshort => "eff-short-1.txt",
+            #[cfg(feature = "list-qwerty")]
+            ListChoice::Qwerty => "orchard-street-qwerty.txt",
+            #[cfg(feature = "list-alpha")]
+            ListChoice::Alpha => "orchard-street-alpha.txt",
+        }
+    }
+
+    /// Short, one-letter code for the list, like "m"
+    pub const fn code(self) -> &'static str {
+        match self {
+            #[cfg(feature = "list-long")]
+            ListChoice::Long => "l",
+            #[cfg(feature = "list-medium")]
+            ListChoice::Medium => "m",
+            #[cfg(feature = "list-eff")]
+            ListChoice::Eff => "e",
+            #[cfg(feature = "list-mnemonicode")]
+            ListChoice::Mnemonicode => "n",
+            #[cfg(feature = "list-eff-short")]
+            ListChoice::Effshort => "s",
+            #[cfg(feature = "list-qwerty")]
+            ListChoice::Qwerty => "q",
+            #[cfg(feature = "list-alpha")]
+            ListChoice::Alpha => "a",
+        }
+    }
+}
+
+/// Facts about a built-in list. These aren't written out by hand: build.rs measures each list in
+/// `word-lists/` when Phraze is built (see `phraze::list_metadata`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListMetadata {
+    pub word_count: usize,
+    pub bits_per_word: f64,
+}
+
+impl fmt::Display for ListChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Accepts either a list's canonical name ("eff-short") or its one-letter code ("s"), in any case
+impl FromStr for ListChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        ListChoice::ALL
+            .iter()
+            .find(|list_choice| list_choice.name() == s || list_choice.code() == s)
+            .copied()
+            .ok_or(format!("'{}' isn't one of the built-in word lists", s))
+    }
+}
+
+#[test]
+fn can_parse_and_display_list_choices_by_name_and_code() {
+    for list_choice in ListChoice::ALL {
+        assert_eq!(list_choice.to_string().parse(), Ok(*list_choice));
+        assert_eq!(list_choice.code().parse(), Ok(*list_choice));
+    }
+    #[cfg(feature = "list-eff-short")]
+    {
+        assert_eq!("eff-short".parse(), Ok(ListChoice::Effshort));
+        assert_eq!("S".parse(), Ok(ListChoice::Effshort));
+        assert_eq!(ListChoice::Effshort.to_string(), "eff-short");
+    }
+    assert!("not-a-list".parse::<ListChoice>().is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn can_deserialize_list_choices_by_name_and_code() {
+    use serde::Deserialize;
+    use serde::de::IntoDeserializer;
+    use serde::de::value::{Error as ValueError, StrDeserializer};
+    for list_choice in ListChoice::ALL {
+        for s in [list_choice.name(), list_choice.code()] {
+            let deserializer: StrDeserializer<ValueError> = s.into_deserializer();
+            assert_eq!(ListChoice::deserialize(deserializer), Ok(*list_choice));
+        }
+    }
+    let deserializer: StrDeserializer<ValueError> = "not-a-list".into_deserializer();
+    assert!(ListChoice::deserialize(deserializer).is_err());
+}
@@ -0,0 +1,296 @@
+//! Facts about word lists: how many words they have, how much entropy each word adds, whether
+//! passphrases made without a separator can be read back only one way, and so on. These are
+//! calculated from the lists themselves, rather than written out by hand, so they're correct for
+//! user lists too. `phraze lists` prints them.
+use crate::DEFAULT_MINIMUM_ENTROPY;
+use crate::calculate_number_words_needed_from_bits_per_word;
+use crate::keyboard_layouts::KeyboardLayout;
+use crate::list_checks::is_uniquely_decodable;
+use crate::list_choice::ListChoice;
+use crate::mnemonic_encoding::format_hex;
+use sha2::{Digest, Sha256};
+
+/// A built-in word list, with its full name and where it came from
+pub struct BuiltInList {
+    pub list_choice: ListChoice,
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+// These go unused if none of the lists from a source are built in
+#[allow(dead_code)]
+const ORCHARD_STREET_SOURCE: &str =
+    "Orchard Street Wordlists (https://github.com/sts10/orchard-street-wordlists), CC BY-SA 4.0";
+#[allow(dead_code)]
+const EFF_SOURCE: &str = "Electronic Frontier Foundation (https://www.eff.org/dice), CC BY 4.0";
+
+/// Every list that's built in to this copy of Phraze
+pub const BUILT_IN_LISTS: &[BuiltInList] = &[
+    #[cfg(feature = "list-medium")]
+    BuiltInList {
+        list_choice: ListChoice::Medium,
+        name: "Orchard Street Medium List",
+        source: ORCHARD_STREET_SOURCE,
+    },
+    #[cfg(feature = "list-long")]
+    BuiltInList {
+        list_choice: ListChoice::Long,
+        name: "Orchard Street Long List",
+        source: ORCHARD_STREET_SOURCE,
+    },
+    #[cfg(feature = "list-eff")]
+    BuiltInList {
+        list_choice: ListChoice::Eff,
+        name: "EFF Long List",
+        source: EFF_SOURCE,
+    },
+    #[cfg(feature = "list-mnemonicode")]
+    BuiltInList {
+        list_choice: ListChoice::Mnemonicode,
+        name: "Mnemonicode List",
+        source: "Mnemonicode by Oren Tirosh (https://github.com/singpolyma/mnemonicode), MIT License",
+    },
+    #[cfg(feature = "list-eff-short")]
+    BuiltInList {
+        list_choice: ListChoice::Effshort,
+        name: "EFF Short List",
+        source: EFF_SOURCE,
+    },
+    #[cfg(feature = "list-qwerty")]
+    BuiltInList {
+        list_choice: ListChoice::Qwerty,
+        name: "Orchard Street QWERTY List",
+        source: ORCHARD_STREET_SOURCE,
+    },
+    #[cfg(feature = "list-alpha")]
+    BuiltInList {
+        list_choice: ListChoice::Alpha,
+        name: "Orchard Street Alpha List",
+        source: ORCHARD_STREET_SOURCE,
+    },
+];
+
+/// Statistics about a word list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListStats {
+    pub word_count: usize,
+    /// Bits of entropy each word adds. For a weighted list, this is the min-entropy, which is
+    /// what Phraze sizes passphrases by.
+    pub bits_per_word: f64,
+    /// For a weighted list, the average bits of entropy each word adds (its Shannon entropy)
+    pub shannon_bits_per_word: Option<f64>,
+    /// Mean length of words on the list, in characters
+    pub average_word_length: f64,
+    /// Bits of entropy per character of an average word
+    pub bits_per_character: f64,
+    /// Whether every passphrase made from this list without a separator can only be split into
+    /// words one way
+    pub uniquely_decodable: bool,
+    /// Fewest characters needed from the start of each word to tell every word on the list apart
+    pub shortest_unique_prefix: usize,
+    /// How many words Phraze uses, by default, to get to 80 bits of entropy, or `None` if the
+    /// list's words add no entropy, so no number of them would get there
+    pub words_for_default_entropy: Option<usize>,
+    /// Typing effort: mean distance travelled from key to key, in key widths, per character of a
+    /// word typed on a QWERTY keyboard
+    pub qwerty_keys_per_character: f64,
+}
+
+impl ListStats {
+    pub fn from_list<T: AsRef<str>>(list: &[T]) -> Self {
+        ListStats::from_weighted_list(list, None)
+    }
+
+    /// Same as `from_list`, but for a list that may be weighted, given the weight of each word
+    /// on the list
+    pub fn from_weighted_list<T: AsRef<str>>(list: &[T], weights: Option<&[f64]>) -> Self {
+        let word_count = list.len();
+        let bits_per_word = match weights {
+            Some(weights) => min_entropy_per_word(weights),
+            None => (word_count as f64).log2(),
+        };
+        let total_length: usize = list.iter().map(|word| word.as_ref().chars().count()).sum();
+        let average_word_length = if word_count == 0 {
+            0.0
+        } else {
+            total_length as f64 / word_count as f64
+        };
+        let bits_per_character = if average_word_length == 0.0 {
+            0.0
+        } else {
+            bits_per_word / average_word_length
+        };
+        ListStats {
+            word_count,
+            bits_per_word,
+            shannon_bits_per_word: weights.map(shannon_entropy_per_word),
+            average_word_length,
+            bits_per_character,
+            uniquely_decodable: is_uniquely_decodable(list),
+            shortest_unique_prefix: shortest_unique_prefix_length(list),
+            words_for_default_entropy: calculate_number_words_needed_from_bits_per_word(
+                None,
+                Some(DEFAULT_MINIMUM_ENTROPY),
+                0,
+                bits_per_word,
+            )
+            .ok(),
+            qwerty_keys_per_character: keys_per_character(list, KeyboardLayout::Qwerty),
+        }
+    }
+}
+
+/// Mean distance travelled from key to key, in key widths, per character typing each word on the
+/// list on the given keyboard layout. Travel between words isn't counted, since it depends on
+/// the separator.
+pub fn keys_per_character<T: AsRef<str>>(list: &[T], layout: KeyboardLayout) -> f64 {
+    let total_travel: f64 = list
+        .iter()
+        .map(|word| layout.travel_distance(word.as_ref()))
+        .sum();
+    let total_length: usize = list.iter().map(|word| word.as_ref().chars().count()).sum();
+    if total_length == 0 {
+        0.0
+    } else {
+        total_travel / total_length as f64
+    }
+}
+
+/// Fewest characters needed from the start of every word to tell each word on the list apart.
+/// A word shorter than that is just the whole word.
+pub fn shortest_unique_prefix_length<T: AsRef<str>>(list: &[T]) -> usize {
+    let mut sorted_words: Vec<&str> = list.iter().map(|word| word.as_ref()).collect();
+    sorted_words.sort_unstable();
+    sorted_words.dedup();
+    // In sorted order, the words that share the longest prefix with each word are its neighbors
+    sorted_words
+        .windows(2)
+        .map(|pair| {
+            pair[0]
+                .chars()
+                .zip(pair[1].chars())
+                .take_while(|(a, b)| a == b)
+                .count()
+                + 1
+        })
+        .max()
+        .unwrap_or(if sorted_words.is_empty() { 0 } else { 1 })
+}
+
+/// A short fingerprint of a word list, like "6883-ada0-490e-1bd8", so that two people can check
+/// that they're making passphrases from the same list. It only depends on which words are on the
+/// list, not their order or any duplicates, so it's the same for two files that Phraze would read
+/// in as the same list. For a weighted list, give the weight of each word too: the same words
+/// with different weights make different passphrases, so they get a different fingerprint.
+pub fn list_fingerprint<T: AsRef<str>>(list: &[T], weights: Option<&[f64]>) -> String {
+    let mut sorted_words: Vec<(&str, Option<f64>)> = list
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.as_ref(), weights.map(|weights| weights[i])))
+        .collect();
+    sorted_words.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    sorted_words.dedup_by(|a, b| a.0 == b.0);
+    let mut hasher = Sha256::new();
+    for (word, weight) in sorted_words {
+        hasher.update(word.as_bytes());
+        if let Some(weight) = weight {
+            hasher.update(format!("\t{}", weight).as_bytes());
+        }
+        hasher.update(b"\n");
+    }
+    let hex = format_hex(&hasher.finalize()[..8]);
+    [&hex[0..4], &hex[4..8], &hex[8..12], &hex[12..16]].join("-")
+}
+
+/// Min-entropy of one word picked from a weighted list, in bits: -log2 of the chance of the
+/// most likely word. An attacker would guess the most likely words first, so this, rather than
+/// the average, is how much a word can be counted on to add. For a list where every word is
+/// equally likely, it's the same as log2 of the list's length.
+pub fn min_entropy_per_word(weights: &[f64]) -> f64 {
+    let total: f64 = weights.iter().sum();
+    let most_likely = weights.iter().copied().fold(0.0, f64::max);
+    -(most_likely / total).log2()
+}
+
+/// Shannon entropy of one word picked from a weighted list, in bits: the average number of bits
+/// a word adds. This is never less than the min-entropy.
+pub fn shannon_entropy_per_word(weights: &[f64]) -> f64 {
+    let total: f64 = weights.iter().sum();
+    weights
+        .iter()
+        .map(|weight| weight / total)
+        .filter(|probability| *probability > 0.0)
+        .map(|probability| -probability * probability.log2())
+        .sum()
+}
+
+#[test]
+fn can_find_shortest_unique_prefix_length() {
+    assert_eq!(
+        shortest_unique_prefix_length(&["apple", "apricot", "banana"]),
+        3
+    );
+    // "add" needs all 3 of its characters, and "adding" needs a 4th to tell it from "add"
+    assert_eq!(shortest_unique_prefix_length(&["add", "adding", "zoo"]), 4);
+    assert_eq!(shortest_unique_prefix_length(&["solo"]), 1);
+}
+
+#[test]
+fn can_measure_typing_effort_of_a_list() {
+    // Every key of "as" is right next to the one before it on QWERTY, and "aa" never moves
+    assert_eq!(
+        keys_per_character(&["as", "aa"], KeyboardLayout::Qwerty),
+        0.25
+    );
+    assert_eq!(keys_per_character(&["aa"], KeyboardLayout::Qwerty), 0.0);
+    let empty: [&str; 0] = [];
+    assert_eq!(keys_per_character(&empty, KeyboardLayout::Qwerty), 0.0);
+}
+
+#[test]
+fn fingerprint_only_depends_on_which_words_are_on_list() {
+    let fingerprint = list_fingerprint(&["apple", "banana", "cherry"], None);
+    assert_eq!(fingerprint, "6883-ada0-490e-1bd8");
+    assert_eq!(
+        list_fingerprint(&["cherry", "apple", "banana", "apple"], None),
+        fingerprint
+    );
+    assert_ne!(list_fingerprint(&["apple", "banana"], None), fingerprint);
+}
+
+#[test]
+fn fingerprint_of_weighted_list_depends_on_weights() {
+    let words = ["apple", "banana", "cherry"];
+    let fingerprint = list_fingerprint(&words, Some(&[3.0, 1.0, 1.0]));
+    assert_ne!(fingerprint, list_fingerprint(&words, None));
+    assert_ne!(
+        fingerprint,
+        list_fingerprint(&words, Some(&[1.0, 1.0, 1.0]))
+    );
+    assert_eq!(
+        list_fingerprint(&["cherry", "apple", "banana"], Some(&[1.0, 3.0, 1.0])),
+        fingerprint
+    );
+}
+
+#[test]
+fn stats_of_weighted_list_use_min_entropy() {
+    let words = ["apple", "banana", "cherry"];
+    let stats = ListStats::from_weighted_list(&words, Some(&[2.0, 1.0, 1.0]));
+    assert_eq!(stats.bits_per_word, 1.0);
+    assert_eq!(stats.shannon_bits_per_word, Some(1.5));
+    assert_eq!(stats.words_for_default_entropy, Some(80));
+    let stats = ListStats::from_list(&words);
+    assert_eq!(stats.bits_per_word, 3_f64.log2());
+    assert_eq!(stats.shannon_bits_per_word, None);
+}
+
+#[test]
+fn can_calculate_entropy_of_weighted_lists() {
+    // Equal weights are the same as an unweighted list of 4 words
+    assert_eq!(min_entropy_per_word(&[1.0, 1.0, 1.0, 1.0]), 2.0);
+    assert_eq!(shannon_entropy_per_word(&[1.0, 1.0, 1.0, 1.0]), 2.0);
+    // Most likely word has a 1 in 2 chance
+    assert_eq!(min_entropy_per_word(&[2.0, 1.0, 1.0]), 1.0);
+    assert_eq!(shannon_entropy_per_word(&[2.0, 1.0, 1.0]), 1.5);
+}
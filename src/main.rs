@@ -1,57 +1,1163 @@
-use crate::cli::Args;
-use crate::file_reader::read_in_custom_list;
-use clap::Parser;
+use crate::alphabets::Alphabet;
+use crate::casing::title_case;
+use crate::check::print_check_report;
+use crate::checksum::has_valid_checksum_word;
+use crate::cli::{Args, Command, ListSelection};
+use crate::decompose::{WordLookup, decompose};
+use crate::file_reader::{
+    ListHeader, WeightedList, is_stdin, merge_word_lists, parse_list_file, parse_tagged_list_file,
+    read_list_file,
+};
+use crate::keyboard_layouts::KeyboardLayout;
+use crate::list_checks::is_uniquely_decodable;
+use crate::list_choice::ListChoice;
+use crate::list_info::{
+    BUILT_IN_LISTS, ListStats, list_fingerprint, min_entropy_per_word, shannon_entropy_per_word,
+};
+use crate::mnemonic_encoding::{decode_word_indexes, encode_bytes, format_hex, parse_hex};
+use crate::pinned_lists::{check_list_sha256, find_pinned_sha256, parse_sha256, pinned_lists_file};
+use crate::pseudo_words::{Letter, PseudoWords, SyllablePattern};
+use crate::recover::{Candidate, find_candidates, rank_combinations, split_pattern};
+use crate::separators::{check_separator_fits_charset, separator_length};
+use crate::templates::Template;
+use crate::unicode_normalization_check::detect_normalization_form;
+use crate::user_lists::{find_all_user_lists, find_user_list, word_list_directories};
+use clap::FromArgMatches;
+use clap::parser::ValueSource;
 use phraze::*;
+use rand::distr::weighted::WeightedIndex;
+use std::collections::{HashMap, HashSet};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 fn main() -> Result<(), String> {
-    let opt = Args::parse();
+    let matches = Args::command_with_list_metadata(&list_metadata).get_matches();
+    let mut opt = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    opt.apply_preset(&matches);
 
-    // Check for a rare but potentially dangerous combination of settings
-    if opt.custom_list_file_path.is_some() && opt.separator.is_empty() && !opt.title_case {
-        let error_msg = "Must use a separator or Title Case when using a custom word list";
-        return Err(error_msg.to_string());
+    // If user asked for something other than generating passphrases, do that instead
+    if let Some(command) = &opt.command {
+        return run_command(command);
     }
 
-    match &opt.custom_list_file_path {
-        Some(custom_list_file_path) => {
-            generate_passphrases(&opt, &read_in_custom_list(custom_list_file_path)?)
+    // Use the --list, unless only custom lists were given. If both were given, merge them.
+    let list_given = matches.value_source("list_choice") == Some(ValueSource::CommandLine);
+    let mut word_list_sources = vec![];
+    if opt.custom_list_file_paths.is_empty() || list_given {
+        word_list_sources.push(word_list_source(&opt.list_choice, &None)?);
+    }
+    word_list_sources.extend(
+        opt.custom_list_file_paths
+            .iter()
+            .map(|path| WordListSource::File(path.to_path_buf())),
+    );
+
+    if let Some(template) = &opt.template {
+        return generate_passphrases_from_template(&opt, template, &word_list_sources);
+    }
+    if let Some(pattern) = &opt.pseudo_words {
+        // Pseudo-words are made up from letters, not picked from a list (clap already rejects
+        // --custom-list)
+        if list_given {
+            return Err("Can't use -l with --pseudo-words".to_string());
+        }
+        return generate_pseudo_word_passphrases(&opt, pattern);
+    }
+    if let Some(alphabet) = &opt.chars {
+        // Passwords have no words to separate, title-case or pick from a list, so rather than
+        // quietly ignore these options, say so
+        for (id, option) in [
+            ("separator", "-s"),
+            ("title_case", "-t"),
+            ("list_choice", "-l"),
+        ] {
+            if matches.value_source(id) == Some(ValueSource::CommandLine) {
+                return Err(format!("Can't use {} with --chars", option));
+            }
+        }
+        return generate_passwords(&opt, alphabet);
+    }
+    match word_list_sources.as_slice() {
+        [WordListSource::BuiltIn(_)] if opt.custom_list_sha256.is_some() => {
+            Err("--custom-list-sha256 only works with custom word lists".to_string())
+        }
+        [WordListSource::BuiltIn(list_choice)] => {
+            generate_passphrases(&opt, fetch_list(*list_choice), None)
+        }
+        _ => {
+            let (word_list, weights) = read_in_word_lists(&opt, &word_list_sources)?;
+            check_custom_list(&opt, &word_list)?;
+            generate_passphrases(&opt, &word_list, weights.as_deref())
+        }
+    }
+}
+
+/// Read in every given word list, and merge them into one. If the only list is a weighted list,
+/// its weights are returned too.
+fn read_in_word_lists(
+    opt: &Args,
+    sources: &[WordListSource],
+) -> Result<(Vec<String>, Option<Vec<f64>>), String> {
+    let file_count = sources
+        .iter()
+        .filter(|source| matches!(source, WordListSource::File(_)))
+        .count();
+    if opt.custom_list_sha256.is_some() && file_count > 1 {
+        return Err("--custom-list-sha256 only works with a single custom word list".to_string());
+    }
+    // `phraze verify` only takes one list, so it couldn't check a word from a merged one
+    if opt.checksum_word && sources.len() > 1 {
+        return Err("Can't add a checksum word to a passphrase made from merged lists".to_string());
+    }
+    let stdin_count = sources
+        .iter()
+        .filter(|source| matches!(source, WordListSource::File(path) if is_stdin(path)))
+        .count();
+    if stdin_count > 1 {
+        return Err("Only one word list can be read from stdin".to_string());
+    }
+    let mut word_lists = vec![];
+    for source in sources {
+        match source {
+            WordListSource::BuiltIn(list_choice) => word_lists.push(
+                fetch_list(*list_choice)
+                    .iter()
+                    .map(|word| word.to_string())
+                    .collect(),
+            ),
+            WordListSource::File(path) => {
+                let contents = read_pinned_list_file(opt.custom_list_sha256.as_deref(), path)?;
+                let (word_list, weights, header) = parse_list_file(path, &contents)?;
+                if opt.verbose {
+                    print_list_header(&header);
+                }
+                if weights.is_some() {
+                    // There's no one right way to weigh words from a weighted list against
+                    // words from an unweighted one
+                    if sources.len() > 1 {
+                        return Err(
+                            "A weighted word list can't be merged with other lists".to_string()
+                        );
+                    }
+                    return Ok((word_list, weights));
+                }
+                word_lists.push(word_list);
+            }
         }
-        None => generate_passphrases(&opt, fetch_list(opt.list_choice)),
+    }
+    // A single list is used just as it is, in its own order and normalization form
+    match word_lists.len() {
+        1 => Ok((word_lists.remove(0), None)),
+        _ => Ok((merge_word_lists(&word_lists), None)),
+    }
+}
+
+/// Read the contents of a word list file. If the list is pinned to a hash, either by the given
+/// --custom-list-sha256 or in the pins file, make sure it hasn't changed.
+fn read_pinned_list_file(custom_list_sha256: Option<&str>, path: &Path) -> Result<Vec<u8>, String> {
+    let expected_sha256 = match custom_list_sha256 {
+        Some(sha256) => Some(parse_sha256(sha256)?),
+        None => match pinned_lists_file() {
+            Some(pins_file) => find_pinned_sha256(path, &pins_file)?,
+            None => None,
+        },
     };
+    let contents = read_list_file(path)?;
+    if let Some(expected_sha256) = expected_sha256 {
+        check_list_sha256(path, &contents, &expected_sha256)?;
+    }
+    Ok(contents)
+}
 
+/// Check that a custom (or merged, or tagged) word list is fit to make passphrases from with the
+/// user's settings: it has at least --min-list-size words, and if passphrases won't have a
+/// separator or Title Case, it's uniquely decodable
+fn check_custom_list<T: AsRef<str>>(opt: &Args, word_list: &[T]) -> Result<(), String> {
+    if word_list.len() < opt.minimum_list_size {
+        return Err(format!(
+            "Custom word list has {} words, fewer than the minimum of {}. To use it anyway, lower the minimum with --min-list-size",
+            word_list.len(),
+            opt.minimum_list_size
+        ));
+    }
+    // Without separators or Title Case to mark where words start, a passphrase could be read as
+    // more than one string of words, so it's weaker than we'd estimate
+    if opt.separator.is_empty() && !opt.title_case && !is_uniquely_decodable(word_list) {
+        return Err(
+            "Must use a separator or Title Case with this word list, since it isn't uniquely decodable"
+                .to_string(),
+        );
+    }
     Ok(())
 }
 
-/// This does the real work of the program: generating the passphrases
-fn generate_passphrases<T: AsRef<str> + std::fmt::Display>(opt: &Args, word_list: &[T]) {
-    // Since user can define a minimum entropy, we might have to do a little math to
-    // figure out how many words we need to include in this passphrase.
-    let number_of_words_to_put_in_passphrase = calculate_number_words_needed(
+/// Read in a custom word list for one of the subcommands or `phraze lists`, refusing to use it if
+/// it's pinned in the pins file and doesn't match. Returns its words, any weights and its header.
+fn read_in_pinned_custom_list(path: &Path) -> Result<WeightedList, String> {
+    let contents = read_pinned_list_file(None, path)?;
+    parse_list_file(path, &contents)
+}
+
+/// Generate passphrases that follow the given template of parts of speech, picking each word
+/// from the words on the tagged list with its slot's part of speech
+fn generate_passphrases_from_template(
+    opt: &Args,
+    template: &Template,
+    sources: &[WordListSource],
+) -> Result<(), String> {
+    let path = match sources {
+        [WordListSource::File(path)] => path,
+        [WordListSource::BuiltIn(_)] => {
+            return Err(
+                "There aren't any built-in tagged word lists, so --template needs a tagged list given with --custom-list"
+                    .to_string(),
+            );
+        }
+        _ => return Err("--template only works with a single tagged word list".to_string()),
+    };
+    // `phraze verify` and --max-length only know about passphrases made from a single list
+    if opt.checksum_word {
+        return Err("Can't add a checksum word to a passphrase made from a template".to_string());
+    }
+    if opt.maximum_length.is_some() || opt.minimum_length.is_some() {
+        return Err("Can't use a maximum or minimum length with a template".to_string());
+    }
+    let contents = read_pinned_list_file(opt.custom_list_sha256.as_deref(), path)?;
+    let (tagged_list, header) = parse_tagged_list_file(path, &contents)?;
+    if opt.verbose {
+        print_list_header(&header);
+    }
+    let all_words = tagged_list.all_words();
+    check_custom_list(opt, &all_words)?;
+    if let Some(charset) = &opt.charset {
+        check_separator_fits_charset(&opt.separator, charset)?;
+    }
+    // Title case each slot's list up front and leave out words that aren't in the charset, just
+    // as we would for a single list
+    let form = detect_normalization_form(&all_words);
+    let slot_lists: Vec<Vec<String>> = template
+        .slots
+        .iter()
+        .map(|part_of_speech| {
+            let mut words: Vec<String> = tagged_list
+                .words(*part_of_speech)
+                .iter()
+                .map(|word| {
+                    if opt.title_case {
+                        title_case(word, opt.locale, form)
+                    } else {
+                        word.to_string()
+                    }
+                })
+                .filter(|word| opt.charset.as_ref().is_none_or(|c| c.can_write(word)))
+                .collect();
+            words.sort();
+            words.dedup();
+            check_list_length(words.len())
+                .map_err(|e| format!("Not enough words tagged {}. {}", part_of_speech, e))?;
+            Ok(words)
+        })
+        .collect::<Result<_, String>>()?;
+    let slot_sizes: Vec<usize> = slot_lists.iter().map(|words| words.len()).collect();
+    let number_of_words_to_put_in_passphrase = calculate_number_words_needed_per_slot(
         opt.number_of_words,
         opt.minimum_entropy,
         opt.strength_count,
-        word_list.len(),
+        &slot_sizes,
     );
+    if number_of_words_to_put_in_passphrase == 0 {
+        return Err("Passphrase needs at least 1 word".to_string());
+    }
 
-    // If user enabled verbose option
+    // If user asked for a specific number of words, make sure they know if that's too few
+    let passphrase_entropy = entropy_of_slots(&slot_sizes, number_of_words_to_put_in_passphrase);
+    if opt.number_of_words.is_some() && passphrase_entropy < LOW_ENTROPY_WARNING_THRESHOLD {
+        eprintln!(
+            "WARNING: Passphrase has only an estimated {:.2} bits of entropy, which is weak. Consider using more words.",
+            passphrase_entropy
+        );
+    }
     if opt.verbose {
-        // print entropy information, but use eprint to only print it
-        // to the terminal
+        // Fingerprint the words passphrases can actually be made from, after any charset and
+        // Title Case, so that users on two machines can check they'd get the same passphrases
+        let slot_words: Vec<&String> = slot_lists.iter().flatten().collect();
+        eprintln!(
+            "Word list fingerprint: {}",
+            list_fingerprint(&slot_words, None)
+        );
+        let slot_sizes_as_strings: Vec<String> = slot_sizes
+            .iter()
+            .map(|slot_size| slot_size.to_string())
+            .collect();
+        // Slots can be different sizes, so the bits per word is an average over the passphrase
         print_entropy(
+            "passphrase",
             number_of_words_to_put_in_passphrase,
-            word_list.len(),
+            "words",
+            passphrase_entropy / number_of_words_to_put_in_passphrase.max(1) as f64,
+            &format!(
+                "from a template whose slots have {} words",
+                slot_sizes_as_strings.join(", ")
+            ),
             opt.n_passphrases,
         );
     }
 
-    // Now we can (finally) generate and print some number of passphrases
+    let slot_lists: Vec<&[String]> = slot_lists.iter().map(|words| words.as_slice()).collect();
     for _ in 0..opt.n_passphrases {
-        let passphrase = generate_a_passphrase(
+        let passphrase = generate_a_passphrase_from_template_with_rng(
+            &mut rand::rng(),
+            &slot_lists,
+            number_of_words_to_put_in_passphrase,
+            &opt.separator,
+            opt.charset.as_ref(),
+        )?;
+        println!("{}", passphrase);
+        if let Some(layout) = opt.layout {
+            print_typing_effort(&passphrase, "passphrase", layout);
+        }
+    }
+    Ok(())
+}
+
+/// Generate random passwords of characters from the given alphabet. The alphabet is used as a word
+/// list of one-character words, so passwords are sized and generated just like passphrases.
+fn generate_passwords(opt: &Args, alphabet: &Alphabet) -> Result<(), String> {
+    if opt.checksum_word {
+        return Err("Can't add a checksum word to a password".to_string());
+    }
+    let mut chars = alphabet.chars();
+    // If user gave a charset, leave out any characters that aren't in it
+    if let Some(charset) = &opt.charset {
+        chars.retain(|c| charset.contains(*c));
+    }
+    let list: Vec<String> = chars.iter().map(|c| c.to_string()).collect();
+    check_list_length(list.len())
+        .map_err(|_| format!("The {} alphabet doesn't have enough characters", alphabet))?;
+    let number_of_characters = calculate_number_words_needed(
+        opt.number_of_words,
+        opt.minimum_entropy,
+        opt.strength_count,
+        list.len(),
+    )?
+    .max(opt.minimum_length.unwrap_or(0));
+    if number_of_characters == 0 {
+        return Err("Password needs at least 1 character".to_string());
+    }
+    if let Some(maximum_length) = opt.maximum_length
+        && number_of_characters > maximum_length
+    {
+        return Err(format!(
+            "Password needs {} characters, more than the maximum length of {}. Try a bigger alphabet",
+            number_of_characters, maximum_length
+        ));
+    }
+
+    let password_entropy = (list.len() as f64).log2() * number_of_characters as f64;
+    if opt.number_of_words.is_some() && password_entropy < LOW_ENTROPY_WARNING_THRESHOLD {
+        eprintln!(
+            "WARNING: Password has only {:.2} bits of entropy, which is weak. Consider using more characters.",
+            password_entropy
+        );
+    }
+    if opt.verbose {
+        print_entropy(
+            "password",
+            number_of_characters,
+            "characters",
+            (list.len() as f64).log2(),
+            &format!("from an alphabet of {} characters", list.len()),
+            opt.n_passphrases,
+        );
+    }
+
+    for _ in 0..opt.n_passphrases {
+        let password = generate_a_passphrase_with_rng(
+            &mut rand::rng(),
+            number_of_characters,
+            "",
+            false,
+            false,
+            None,
+            &list,
+        )?;
+        println!("{}", password);
+        if let Some(layout) = opt.layout {
+            print_typing_effort(&password, "password", layout);
+        }
+    }
+    Ok(())
+}
+
+/// Generate passphrases out of pseudo-words that follow the given pattern of consonants and vowels
+fn generate_pseudo_word_passphrases(opt: &Args, pattern: &SyllablePattern) -> Result<(), String> {
+    if opt.checksum_word {
+        return Err("Can't add a checksum word to a passphrase of pseudo-words".to_string());
+    }
+    let mut pseudo_words = PseudoWords::new(pattern.clone());
+    // If user gave a charset, only use letters that can be written in it (as capitals too, if
+    // they'll be title cased). Entropy is then figured from the letters that are left.
+    if let Some(charset) = &opt.charset {
+        check_separator_fits_charset(&opt.separator, charset)?;
+        pseudo_words.keep_letters(|c| {
+            charset.contains(c) && (!opt.title_case || charset.contains(c.to_ascii_uppercase()))
+        });
+    }
+    for letter in &pattern.letters {
+        check_list_length(pseudo_words.letters_for(*letter).len()).map_err(|_| {
+            format!(
+                "Not enough {}s to make pseudo-words from",
+                match letter {
+                    Letter::Consonant => "consonant",
+                    Letter::Vowel => "vowel",
+                }
+            )
+        })?;
+    }
+    let bits_per_pseudo_word = pseudo_words.bits_per_pseudo_word();
+    let number_of_words_to_put_in_passphrase = words_for_minimum_length(
+        calculate_number_words_needed_from_bits_per_word(
+            opt.number_of_words,
+            opt.minimum_entropy,
+            opt.strength_count,
+            bits_per_pseudo_word,
+        )?,
+        pseudo_words.length(),
+        separator_length(&opt.separator),
+        opt.minimum_length.unwrap_or(0),
+    );
+    if number_of_words_to_put_in_passphrase == 0 {
+        return Err("Passphrase needs at least 1 word".to_string());
+    }
+    // Every pseudo-word is the same length, so unlike with a word list, there's no leaving out
+    // long words to make the passphrase fit. It either fits or it doesn't.
+    if let Some(maximum_length) = opt.maximum_length {
+        let length = number_of_words_to_put_in_passphrase * pseudo_words.length()
+            + (number_of_words_to_put_in_passphrase - 1) * separator_length(&opt.separator);
+        if length > maximum_length {
+            return Err(format!(
+                "Passphrase of {} pseudo-words would be {} characters long, more than the maximum length of {}. Try a longer pattern or a shorter separator",
+                number_of_words_to_put_in_passphrase, length, maximum_length
+            ));
+        }
+    }
+
+    let passphrase_entropy = bits_per_pseudo_word * number_of_words_to_put_in_passphrase as f64;
+    if opt.number_of_words.is_some() && passphrase_entropy < LOW_ENTROPY_WARNING_THRESHOLD {
+        eprintln!(
+            "WARNING: Passphrase has only {:.2} bits of entropy, which is weak. Consider using more pseudo-words.",
+            passphrase_entropy
+        );
+    }
+    if opt.verbose {
+        // Unlike the other estimates, this one is exact, since we know exactly how many
+        // pseudo-words there are to pick from
+        let number_of_pseudo_words = match pseudo_words.number_of_pseudo_words() {
+            Some(count) => count.to_string(),
+            None => "too many to count".to_string(),
+        };
+        print_entropy(
+            "passphrase",
+            number_of_words_to_put_in_passphrase,
+            "pseudo-words",
+            bits_per_pseudo_word,
+            &format!("from exactly {} possible", number_of_pseudo_words),
+            opt.n_passphrases,
+        );
+    }
+
+    for _ in 0..opt.n_passphrases {
+        let passphrase = generate_a_pseudo_word_passphrase_with_rng(
+            &mut rand::rng(),
+            &pseudo_words,
             number_of_words_to_put_in_passphrase,
             &opt.separator,
             opt.title_case,
-            word_list,
+            opt.charset.as_ref(),
+        )?;
+        println!("{}", passphrase);
+        if let Some(layout) = opt.layout {
+            print_typing_effort(&passphrase, "passphrase", layout);
+        }
+    }
+    Ok(())
+}
+
+/// This does the real work of the program: generating the passphrases. If `weights` are given,
+/// they're the weights of each word on the list, and words are picked in proportion to them.
+fn generate_passphrases<T: AsRef<str>>(
+    opt: &Args,
+    word_list: &[T],
+    weights: Option<&[f64]>,
+) -> Result<(), String> {
+    let word_list: Vec<&str> = word_list.iter().map(|word| word.as_ref()).collect();
+    // Title case the whole list up front, keeping words in the list's normalization form, so
+    // that the checks below see words as they'll appear in the passphrase
+    let title_cased_words: Vec<String>;
+    let mut word_list = if opt.title_case {
+        let form = detect_normalization_form(&word_list);
+        title_cased_words = word_list
+            .iter()
+            .map(|word| title_case(word, opt.locale, form))
+            .collect();
+        title_cased_words.iter().map(|word| word.as_str()).collect()
+    } else {
+        word_list
+    };
+    // Look weights up by word, so they stay with their words as words are left out below. Two
+    // words that title case to the same word become one word, with both their weights.
+    let weight_of: Option<HashMap<&str, f64>> = weights.map(|weights| {
+        let mut weight_of = HashMap::new();
+        for (word, weight) in word_list.iter().zip(weights) {
+            *weight_of.entry(*word).or_insert(0.0) += weight;
+        }
+        word_list.sort_unstable();
+        word_list.dedup();
+        weight_of
+    });
+    let weights_of = |list: &[&str]| -> Option<Vec<f64>> {
+        weight_of
+            .as_ref()
+            .map(|weight_of| list.iter().map(|word| weight_of[word]).collect())
+    };
+    // Each word from a weighted list is only counted on for the list's min-entropy
+    let bits_per_word = |list: &[&str]| match weights_of(list) {
+        Some(weights) => min_entropy_per_word(&weights),
+        None => (list.len() as f64).log2(),
+    };
+    // If user gave a charset, leave out any words that can't be written in it. Entropy is then
+    // figured from the words that are left.
+    let word_list = match &opt.charset {
+        Some(charset) => {
+            // `phraze verify` wouldn't know which words were left off the list
+            if opt.checksum_word {
+                return Err("Can't add a checksum word with a charset".to_string());
+            }
+            check_separator_fits_charset(&opt.separator, charset)?;
+            let words_in_charset: Vec<&str> = word_list
+                .iter()
+                .filter(|word| charset.can_write(word))
+                .copied()
+                .collect();
+            if opt.verbose && words_in_charset.len() < word_list.len() {
+                eprintln!(
+                    "Left out {} words that can't be written in the {} charset",
+                    word_list.len() - words_in_charset.len(),
+                    charset
+                );
+            }
+            words_in_charset
+        }
+        None => word_list,
+    };
+    check_list_length(word_list.len())?;
+    // Since user can define a minimum entropy, we might have to do a little math to
+    // figure out how many words we need to include in this passphrase.
+    let words_needed = |list: &[&str]| {
+        Ok(words_for_minimum_length(
+            calculate_number_words_needed_from_bits_per_word(
+                opt.number_of_words,
+                opt.minimum_entropy,
+                opt.strength_count,
+                bits_per_word(list),
+            )?,
+            list.iter()
+                .map(|word| word.chars().count())
+                .min()
+                .unwrap_or(0),
+            separator_length(&opt.separator),
+            opt.minimum_length.unwrap_or(0),
+        ))
+    };
+    let (word_list, number_of_words_to_put_in_passphrase) = match opt.maximum_length {
+        Some(maximum_length) => {
+            // `phraze verify` wouldn't know which words were left off the list
+            if opt.checksum_word {
+                return Err("Can't add a checksum word with a maximum length".to_string());
+            }
+            words_that_fit(
+                &word_list,
+                words_needed,
+                separator_length(&opt.separator),
+                maximum_length,
+            )?
+        }
+        None => {
+            let number_of_words = words_needed(&word_list)?;
+            (word_list, number_of_words)
+        }
+    };
+    if number_of_words_to_put_in_passphrase == 0 {
+        return Err("Passphrase needs at least 1 word".to_string());
+    }
+
+    let weights = weights_of(&word_list);
+    let weighted_index = match &weights {
+        Some(weights) => match WeightedIndex::new(weights) {
+            Ok(weighted_index) => Some(weighted_index),
+            Err(e) => return Err(format!("Error using word list's weights: {}", e)),
+        },
+        None => None,
+    };
+
+    // If user asked for a specific number of words, make sure they know if that's too few
+    let passphrase_entropy =
+        bits_per_word(&word_list) * number_of_words_to_put_in_passphrase as f64;
+    if opt.number_of_words.is_some() && passphrase_entropy < LOW_ENTROPY_WARNING_THRESHOLD {
+        eprintln!(
+            "WARNING: Passphrase has only an estimated {:.2} bits of entropy, which is weak. Consider using more words.",
+            passphrase_entropy
+        );
+    }
+
+    // If user enabled verbose option
+    if opt.verbose {
+        // So that users on two machines can check they're using the same list. This is the list
+        // after any charset, maximum length and Title Case, since that's what words are picked
+        // from.
+        eprintln!(
+            "Word list fingerprint: {}",
+            list_fingerprint(&word_list, weights.as_deref())
+        );
+        // print entropy information, but use eprint to only print it
+        // to the terminal
+        match &weights {
+            // Min-entropy, which only counts the chance of the most likely word, is the
+            // conservative estimate passphrases are sized by. Shannon entropy is the average.
+            Some(weights) => print_entropy(
+                "passphrase",
+                number_of_words_to_put_in_passphrase,
+                "words",
+                min_entropy_per_word(weights),
+                &format!(
+                    "from a weighted list of {} words, counting min-entropy, or {:.2} bits of Shannon entropy",
+                    word_list.len(),
+                    shannon_entropy_per_word(weights) * number_of_words_to_put_in_passphrase as f64
+                ),
+                opt.n_passphrases,
+            ),
+            None => print_entropy(
+                "passphrase",
+                number_of_words_to_put_in_passphrase,
+                "words",
+                (word_list.len() as f64).log2(),
+                &format!("from a list of {} words", word_list.len()),
+                opt.n_passphrases,
+            ),
+        }
+        // The checksum word is determined by the other words, so it doesn't add any entropy
+        if opt.checksum_word {
+            eprintln!("Plus 1 checksum word, which adds no entropy");
+        }
+    }
+
+    // Now we can (finally) generate and print some number of passphrases
+    for _ in 0..opt.n_passphrases {
+        let passphrase = match &weighted_index {
+            Some(weighted_index) => generate_a_weighted_passphrase_with_rng(
+                &mut rand::rng(),
+                weighted_index,
+                number_of_words_to_put_in_passphrase,
+                &opt.separator,
+                false, // Words have already been title cased, if need be
+                opt.checksum_word,
+                opt.charset.as_ref(),
+                &word_list,
+            )?,
+            None => generate_a_passphrase_with_rng(
+                &mut rand::rng(),
+                number_of_words_to_put_in_passphrase,
+                &opt.separator,
+                false, // Words have already been title cased, if need be
+                opt.checksum_word,
+                opt.charset.as_ref(),
+                &word_list,
+            )?,
+        };
+        println!("{}", passphrase);
+        if let Some(layout) = opt.layout {
+            print_typing_effort(&passphrase, "passphrase", layout);
+        }
+    }
+    Ok(())
+}
+
+/// Print what a custom word list's header says about the list, if it has a header
+fn print_list_header(header: &ListHeader) {
+    let fields = [
+        ("Name", &header.name),
+        ("Language", &header.language),
+        ("License", &header.license),
+    ];
+    for (field, value) in fields {
+        if let Some(value) = value {
+            eprintln!("{}: {}", field, value);
+        }
+    }
+}
+
+/// Print how far one would travel, key to key, typing given passphrase on given keyboard layout.
+/// `noun` is what to call it, like "passphrase" or "password".
+fn print_typing_effort(passphrase: &str, noun: &str, layout: KeyboardLayout) {
+    let travel_distance = layout.travel_distance(passphrase);
+    eprintln!(
+        "Typing this {} on the {} layout travels {:.1} keys ({:.2} keys per character)",
+        noun,
+        layout,
+        travel_distance,
+        travel_distance / passphrase.chars().count().max(1) as f64
+    );
+}
+
+/// Run one of Phraze's subcommands
+fn run_command(command: &Command) -> Result<(), String> {
+    match command {
+        Command::Check {
+            custom_list_file_path,
+        } => {
+            reject_list_from_stdin(custom_list_file_path, "passphrase")?;
+            let passphrase = read_passphrase()?;
+            let (custom_list, custom_weights) = match custom_list_file_path {
+                Some(custom_list_file_path) => {
+                    let (list, weights, _header) =
+                        read_in_pinned_custom_list(custom_list_file_path)?;
+                    (Some(list), weights)
+                }
+                None => (None, None),
+            };
+            print_check_report(
+                &passphrase,
+                custom_list.as_deref(),
+                custom_weights.as_deref(),
+            );
+        }
+        Command::Split {
+            list_choice,
+            custom_list_file_path,
+        } => {
+            reject_list_from_stdin(custom_list_file_path, "passphrase")?;
+            let passphrase = read_passphrase()?;
+            match word_list_source(list_choice, custom_list_file_path)? {
+                WordListSource::File(path) => {
+                    split_passphrase(&passphrase, &read_in_pinned_custom_list(&path)?.0)?
+                }
+                WordListSource::BuiltIn(list_choice) => {
+                    split_passphrase(&passphrase, fetch_list(list_choice))?
+                }
+            }
+        }
+        Command::Verify {
+            list_choice,
+            custom_list_file_path,
+        } => {
+            reject_list_from_stdin(custom_list_file_path, "passphrase")?;
+            let passphrase = read_passphrase()?;
+            match word_list_source(list_choice, custom_list_file_path)? {
+                WordListSource::File(path) => {
+                    verify_passphrase(&passphrase, &read_in_pinned_custom_list(&path)?.0)?
+                }
+                WordListSource::BuiltIn(list_choice) => {
+                    verify_passphrase(&passphrase, fetch_list(list_choice))?
+                }
+            }
+        }
+        Command::Recover {
+            pattern,
+            list_choice,
+            custom_list_file_path,
+            layout,
+            separator,
+            maximum_edits,
+            maximum_candidates,
+        } => {
+            let pattern = match pattern {
+                Some(pattern) => pattern.to_string(),
+                None => {
+                    reject_list_from_stdin(custom_list_file_path, "passphrase")?;
+                    read_passphrase()?
+                }
+            };
+            let settings = RecoverySettings {
+                separator: separator.as_deref(),
+                maximum_edits: *maximum_edits,
+                maximum_candidates: *maximum_candidates,
+                keyboard_layout: *layout,
+            };
+            match word_list_source(list_choice, custom_list_file_path)? {
+                WordListSource::File(path) => {
+                    recover_passphrase(&pattern, &read_in_pinned_custom_list(&path)?.0, settings)?
+                }
+                WordListSource::BuiltIn(list_choice) => recover_passphrase(
+                    &pattern,
+                    fetch_list(list_choice),
+                    RecoverySettings {
+                        keyboard_layout: layout.or(layout_list_was_made_for(list_choice)),
+                        ..settings
+                    },
+                )?,
+            }
+        }
+        Command::Lists => print_available_lists(),
+        Command::Encode {
+            list_choice,
+            custom_list_file_path,
+            separator,
+            hex,
+        } => {
+            reject_list_from_stdin(custom_list_file_path, "input to encode")?;
+            let mut input = vec![];
+            if let Err(e) = std::io::stdin().read_to_end(&mut input) {
+                return Err(format!("Error reading input: {}", e));
+            }
+            let bytes = if *hex {
+                parse_hex(&String::from_utf8_lossy(&input))?
+            } else {
+                input
+            };
+            match word_list_source(list_choice, custom_list_file_path)? {
+                WordListSource::File(path) => {
+                    encode(&bytes, separator, &read_in_pinned_custom_list(&path)?.0)?
+                }
+                WordListSource::BuiltIn(list_choice) => {
+                    encode(&bytes, separator, fetch_list(list_choice))?
+                }
+            }
+        }
+        Command::Decode {
+            list_choice,
+            custom_list_file_path,
+            hex,
+        } => {
+            reject_list_from_stdin(custom_list_file_path, "input to decode")?;
+            let mut input = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+                return Err(format!("Error reading input: {}", e));
+            }
+            let bytes = match word_list_source(list_choice, custom_list_file_path)? {
+                WordListSource::File(path) => {
+                    decode(&input, &read_in_pinned_custom_list(&path)?.0)?
+                }
+                WordListSource::BuiltIn(list_choice) => decode(&input, fetch_list(list_choice))?,
+            };
+            let written = if *hex {
+                writeln!(std::io::stdout(), "{}", format_hex(&bytes))
+            } else {
+                std::io::stdout().write_all(&bytes)
+            };
+            if let Err(e) = written {
+                return Err(format!("Error writing output: {}", e));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Subcommands that read a passphrase or other input from stdin can't read a word list from it
+/// too
+fn reject_list_from_stdin(
+    custom_list_file_path: &Option<PathBuf>,
+    what_stdin_is_for: &str,
+) -> Result<(), String> {
+    match custom_list_file_path {
+        Some(path) if is_stdin(path) => Err(format!(
+            "Can't read the word list from stdin (-c -), since stdin is where the {} is read from",
+            what_stdin_is_for
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Where the words of a word list come from: one of the built-in lists, or a file
+enum WordListSource {
+    BuiltIn(ListChoice),
+    File(PathBuf),
+}
+
+/// Figure out where to get the word list from, given what the user passed to --list and
+/// --custom-list. A --list name that isn't one of the built-in lists is looked up in the user's
+/// word list directories.
+fn word_list_source(
+    list_choice: &ListSelection,
+    custom_list_file_path: &Option<PathBuf>,
+) -> Result<WordListSource, String> {
+    if let Some(custom_list_file_path) = custom_list_file_path {
+        return Ok(WordListSource::File(custom_list_file_path.to_path_buf()));
+    }
+    match list_choice {
+        ListSelection::BuiltIn(list_choice) => Ok(WordListSource::BuiltIn(*list_choice)),
+        ListSelection::Named(name) => match find_user_list(name, &word_list_directories()) {
+            Some(path) => Ok(WordListSource::File(path)),
+            None => Err(format!(
+                "Couldn't find a word list named '{}'. Run `phraze lists` to see every list available.",
+                name
+            )),
+        },
+    }
+}
+
+/// Print every built-in word list, then every word list found in the user's word list
+/// directories, along with some facts about each list
+fn print_available_lists() {
+    println!("Built-in lists:");
+    for built_in_list in BUILT_IN_LISTS {
+        println!(
+            "\n{} ({} or {})",
+            built_in_list.name,
+            built_in_list.list_choice.code(),
+            built_in_list.list_choice
         );
+        print_list_stats(&ListStats::from_list(fetch_list(built_in_list.list_choice)));
+        println!("  Source                    : {}", built_in_list.source);
+    }
+
+    let directories = word_list_directories();
+    let user_lists = find_all_user_lists(&directories);
+    println!("\nUser lists:");
+    if user_lists.is_empty() {
+        println!("\n(none found)");
+    }
+    for (name, path) in user_lists {
+        println!("\n{}", name);
+        match read_in_pinned_custom_list(&path) {
+            Ok((list, weights, _header)) => {
+                print_list_stats(&ListStats::from_weighted_list(&list, weights.as_deref()))
+            }
+            Err(e) => println!("  Couldn't read list: {}", e),
+        }
+        println!("  Source                    : {}", path.display());
+    }
+
+    println!("\nUser lists are read from these directories, in order:");
+    for directory in directories {
+        println!("  {}", directory.display());
+    }
+}
+
+fn print_list_stats(stats: &ListStats) {
+    println!("  Words                     : {}", stats.word_count);
+    match stats.shannon_bits_per_word {
+        Some(shannon_bits_per_word) => println!(
+            "  Bits per word             : {:.3} (min-entropy), {:.3} (Shannon entropy)",
+            stats.bits_per_word, shannon_bits_per_word
+        ),
+        None => println!("  Bits per word             : {:.3}", stats.bits_per_word),
+    }
+    println!(
+        "  Mean word length          : {:.2} characters",
+        stats.average_word_length
+    );
+    println!(
+        "  Bits per character        : {:.3}",
+        stats.bits_per_character
+    );
+    println!(
+        "  Uniquely decodable        : {}",
+        if stats.uniquely_decodable {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    println!(
+        "  Unique character prefix   : {}",
+        stats.shortest_unique_prefix
+    );
+    match stats.words_for_default_entropy {
+        Some(number_of_words) => println!("  Words needed for 80 bits  : {}", number_of_words),
+        None => println!("  Words needed for 80 bits  : none would do"),
+    }
+    println!(
+        "  Typing effort (QWERTY)    : {:.2} keys per character",
+        stats.qwerty_keys_per_character
+    );
+}
+
+/// Check that the last word of the given passphrase is the correct checksum word
+fn verify_passphrase<T: AsRef<str>>(passphrase: &str, word_list: &[T]) -> Result<(), String> {
+    let lookup = WordLookup::new(word_list);
+    let decompositions = decompose(passphrase, &lookup, 1000);
+    if decompositions.is_empty() {
+        return Err("Passphrase can't be broken into words from this word list".to_string());
+    }
+    if decompositions
+        .iter()
+        .any(|d| has_valid_checksum_word(&d.word_indexes, word_list.len()))
+    {
+        println!("Checksum word is correct");
+        Ok(())
+    } else {
+        Err("Checksum word doesn't match. Passphrase may have a typo in it.".to_string())
+    }
+}
+
+/// The QWERTY and Alpha lists were made with a particular keyboard layout in mind, so we can
+/// assume that layout if user didn't specify one
+fn layout_list_was_made_for(list_choice: ListChoice) -> Option<KeyboardLayout> {
+    match list_choice {
+        #[cfg(feature = "list-qwerty")]
+        ListChoice::Qwerty => Some(KeyboardLayout::Qwerty),
+        #[cfg(feature = "list-alpha")]
+        ListChoice::Alpha => Some(KeyboardLayout::Alphabetical),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Settings for `recover_passphrase`
+struct RecoverySettings<'a> {
+    separator: Option<&'a str>,
+    maximum_edits: u32,
+    maximum_candidates: usize,
+    keyboard_layout: Option<KeyboardLayout>,
+}
+
+/// Print a ranked list of candidate passphrases that are close to the given pattern
+fn recover_passphrase<T: AsRef<str>>(
+    pattern: &str,
+    word_list: &[T],
+    settings: RecoverySettings,
+) -> Result<(), String> {
+    let (splits, pattern_separator) = split_pattern(pattern, word_list);
+    if splits.is_empty() {
+        return Err("No words found to recover".to_string());
+    }
+    // Splits share most of their tokens, so only look for each token's candidates once
+    let mut candidates_for_token: HashMap<&str, Vec<Candidate>> = HashMap::new();
+    for token in splits.iter().flatten() {
+        candidates_for_token.entry(token).or_insert_with(|| {
+            find_candidates(
+                token,
+                word_list,
+                settings.maximum_edits,
+                settings.keyboard_layout,
+            )
+        });
+    }
+    let candidates_for_each_split: Vec<Vec<Vec<Candidate>>> = splits
+        .iter()
+        .map(|split| {
+            split
+                .iter()
+                .map(|token| candidates_for_token[token.as_str()].clone())
+                .collect()
+        })
+        .collect();
+    if candidates_for_each_split
+        .iter()
+        .all(|split| split.iter().any(|candidates| candidates.is_empty()))
+    {
+        // Point at a word from the split at every separator, since that's how the user will
+        // count them
+        let (position, token) = splits[0]
+            .iter()
+            .enumerate()
+            .find(|(_, token)| candidates_for_token[token.as_str()].is_empty())
+            .unwrap_or((0, &splits[0][0]));
+        return Err(format!(
+            "Couldn't find any words on the list close to word {} ('{}')",
+            position + 1,
+            token
+        ));
+    }
+
+    let separator = settings
+        .separator
+        .or(pattern_separator.as_deref())
+        .unwrap_or("-");
+    // Different words can make the same passphrase, like "drop-down" and "drop" then "down",
+    // so only print each passphrase once
+    let mut printed = HashSet::new();
+    let passphrases = rank_combinations(&candidates_for_each_split)
+        .map(|combination| {
+            combination
+                .iter()
+                .map(|index| word_list[*index].as_ref())
+                .collect::<Vec<&str>>()
+                .join(separator)
+        })
+        .filter(|passphrase| printed.insert(passphrase.clone()))
+        .take(settings.maximum_candidates);
+    for passphrase in passphrases {
         println!("{}", passphrase);
     }
+    Ok(())
+}
+
+/// Encode bytes as words from the given word list, and print them
+fn encode<T: AsRef<str>>(bytes: &[u8], separator: &str, word_list: &[T]) -> Result<(), String> {
+    if word_list.len() < 2 {
+        return Err("Need a word list of at least 2 words to encode bytes".to_string());
+    }
+    let words: Vec<&str> = encode_bytes(bytes, word_list.len())
+        .iter()
+        .map(|index| word_list[*index].as_ref())
+        .collect();
+    println!("{}", words.join(separator));
+    Ok(())
+}
+
+/// Break encoded input back into words from the given word list, then decode them into bytes
+fn decode<T: AsRef<str>>(input: &str, word_list: &[T]) -> Result<Vec<u8>, String> {
+    // Zero bytes encode to zero words, which `encode` prints as an empty line
+    if input.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let lookup = WordLookup::new(word_list);
+    match decompose(input, &lookup, 2).as_slice() {
+        [decomposition] => Ok(decode_word_indexes(
+            &decomposition.word_indexes,
+            word_list.len(),
+        )),
+        [] => Err("Input can't be broken into words from this word list".to_string()),
+        _ => {
+            Err("Input can be broken into words from this word list more than one way".to_string())
+        }
+    }
+}
+
+/// Print every way the given passphrase can be broken into words from the word list
+fn split_passphrase<T: AsRef<str>>(passphrase: &str, word_list: &[T]) -> Result<(), String> {
+    const MAXIMUM_SEGMENTATIONS: usize = 1000;
+    let lookup = WordLookup::new(word_list);
+    let decompositions = decompose(passphrase, &lookup, MAXIMUM_SEGMENTATIONS);
+    if decompositions.is_empty() {
+        return Err("Passphrase can't be broken into words from this word list".to_string());
+    }
+    if decompositions.len() > 1 {
+        eprintln!(
+            "Passphrase can be broken into words from this word list {}{} different ways:",
+            if decompositions.len() == MAXIMUM_SEGMENTATIONS {
+                "at least "
+            } else {
+                ""
+            },
+            decompositions.len()
+        );
+    }
+    for decomposition in decompositions {
+        let words: Vec<&str> = decomposition
+            .word_indexes
+            .iter()
+            .map(|index| word_list[*index].as_ref())
+            .collect();
+        println!("{}", words.join(" "));
+    }
+    Ok(())
+}
+
+/// Read a passphrase from stdin. If stdin is a terminal, don't echo the passphrase back
+/// as the user types it.
+fn read_passphrase() -> Result<String, String> {
+    let passphrase = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password("Passphrase: ")
+    } else {
+        let mut passphrase = String::new();
+        std::io::stdin()
+            .read_line(&mut passphrase)
+            .map(|_| passphrase)
+    };
+    match passphrase {
+        Ok(passphrase) => Ok(passphrase.trim_end_matches(['\r', '\n']).to_string()),
+        Err(e) => Err(format!("Error reading passphrase: {}", e)),
+    }
 }
@@ -0,0 +1,118 @@
+//! Functions for encoding arbitrary bytes as words, and decoding them back again. A word list of
+//! N words is treated as an alphabet of N "digits," so the bytes are simply converted into a
+//! base-N number.
+//!
+//! Like Base58, each leading zero byte is encoded as a leading "zero" (the first word on the
+//! list), so that the number of leading zeros, and thus the length of the input, survives the
+//! round trip.
+
+/// Encode given bytes as a series of word indexes into a list of `list_length` words.
+pub fn encode_bytes(bytes: &[u8], list_length: usize) -> Vec<usize> {
+    assert!(
+        list_length >= 2,
+        "Need a list of at least 2 words to encode bytes"
+    );
+    let base = list_length as u64;
+    let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+
+    // Repeatedly divide the (big-endian) number by the base, collecting remainders as digits
+    let mut number: Vec<u8> = bytes[leading_zeros..].to_vec();
+    let mut digits = vec![];
+    while !number.is_empty() {
+        let mut remainder: u64 = 0;
+        let mut quotient = vec![];
+        for byte in number {
+            let accumulator = remainder * 256 + byte as u64;
+            let digit = accumulator / base;
+            remainder = accumulator % base;
+            if !quotient.is_empty() || digit != 0 {
+                quotient.push(digit as u8);
+            }
+        }
+        digits.push(remainder as usize);
+        number = quotient;
+    }
+
+    let mut word_indexes = vec![0; leading_zeros];
+    word_indexes.extend(digits.iter().rev());
+    word_indexes
+}
+
+/// Decode a series of word indexes, from a list of `list_length` words, back into bytes.
+pub fn decode_word_indexes(word_indexes: &[usize], list_length: usize) -> Vec<u8> {
+    let leading_zeros = word_indexes.iter().take_while(|index| **index == 0).count();
+
+    // Build up the number as little-endian bytes, multiplying by the base for each digit
+    let mut number: Vec<u8> = vec![];
+    for digit in &word_indexes[leading_zeros..] {
+        let mut carry = *digit as u64;
+        for byte in number.iter_mut() {
+            let accumulator = *byte as u64 * list_length as u64 + carry;
+            *byte = (accumulator & 0xff) as u8;
+            carry = accumulator >> 8;
+        }
+        while carry > 0 {
+            number.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut bytes = vec![0; leading_zeros];
+    bytes.extend(number.iter().rev());
+    bytes
+}
+
+/// Parse a string of hexadecimal digits (ignoring whitespace) into bytes.
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<char> = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex input must have an even number of digits".to_string());
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair: String = pair.iter().collect();
+            u8::from_str_radix(&pair, 16).map_err(|_| format!("'{}' isn't valid hex", pair))
+        })
+        .collect()
+}
+
+/// Format bytes as a string of lowercase hexadecimal digits.
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn can_encode_and_decode_bytes_losslessly() {
+    let inputs: [&[u8]; 6] = [
+        &[],
+        &[0],
+        &[0, 0, 0],
+        &[0, 0, 1, 2, 3],
+        &[255, 255, 255, 255, 255, 255, 255, 255, 255],
+        &[0, 222, 173, 190, 239, 0, 0],
+    ];
+    for list_length in [2, 1296, 1633, 7776, 8192, 17576] {
+        for input in inputs {
+            let word_indexes = encode_bytes(input, list_length);
+            assert!(word_indexes.iter().all(|index| *index < list_length));
+            assert_eq!(decode_word_indexes(&word_indexes, list_length), input);
+        }
+    }
+}
+
+#[test]
+fn can_encode_bytes_as_expected_digits() {
+    // 256 in base 10 is "2 5 6"
+    assert_eq!(encode_bytes(&[1, 0], 10), vec![2, 5, 6]);
+    // Each leading zero byte becomes a leading zero word
+    assert_eq!(encode_bytes(&[0, 0, 1, 0], 10), vec![0, 0, 2, 5, 6]);
+}
+
+#[test]
+fn can_parse_and_format_hex() {
+    assert_eq!(parse_hex("00ff 10").unwrap(), vec![0, 255, 16]);
+    assert_eq!(format_hex(&[0, 255, 16]), "00ff10");
+    assert!(parse_hex("abc").is_err());
+    assert!(parse_hex("zz").is_err());
+}
@@ -0,0 +1,164 @@
+//! A team sharing a word list wants to know it hasn't been tampered with. Someone who trims a
+//! shared list down to 100 words would otherwise have Phraze quietly make weak passphrases. So
+//! custom lists can be "pinned" to a SHA-256 hash of the file, either with --custom-list-sha256
+//! or in a pins file, and Phraze refuses to use a pinned list that doesn't match.
+//!
+//! The pins file is `$XDG_CONFIG_HOME/phraze/pinned-lists`, which defaults to
+//! `~/.config/phraze/pinned-lists`. It uses the same format `sha256sum` prints, so it can be
+//! made with `sha256sum team.txt >> ~/.config/phraze/pinned-lists`: one hash, then whitespace,
+//! then the path of the list, per line. Relative paths are relative to the pins file's directory.
+use crate::file_reader::is_stdin;
+use crate::mnemonic_encoding::format_hex;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where Phraze looks for the pins file
+pub fn pinned_lists_file() -> Option<PathBuf> {
+    let config_home = match env::var_os("XDG_CONFIG_HOME") {
+        Some(config_home) if !config_home.is_empty() => Some(PathBuf::from(config_home)),
+        _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+    };
+    config_home.map(|config_home| config_home.join("phraze").join("pinned-lists"))
+}
+
+/// Find the hash the given pins file pins the given word list file to, if any. A pins file
+/// that doesn't exist pins nothing. Any other trouble reading the pins file, or finding the list,
+/// is an error: if we can't tell whether a list is pinned, it's not safe to use it.
+pub fn find_pinned_sha256(list_path: &Path, pins_file: &Path) -> Result<Option<String>, String> {
+    let contents = match fs::read_to_string(pins_file) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(format!(
+                "Error reading pins file {}: {}",
+                pins_file.display(),
+                e
+            ));
+        }
+    };
+    // A list from stdin has no path to pin it by
+    if is_stdin(list_path) {
+        return Ok(None);
+    }
+    let pins_directory = pins_file.parent().unwrap_or(Path::new(""));
+    let list_path = list_path
+        .canonicalize()
+        .map_err(|e| format!("Error finding word list {}: {}", list_path.display(), e))?;
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid_line = || {
+            format!(
+                "Line {} of {} isn't a SHA-256 hash followed by a file path",
+                line_number + 1,
+                pins_file.display()
+            )
+        };
+        let (sha256, pinned_path) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid_line)?;
+        let sha256 = parse_sha256(sha256).map_err(|_| invalid_line())?;
+        // sha256sum marks files it read in binary mode with a *
+        let pinned_path = pinned_path.trim_start();
+        let pinned_path = pinned_path.strip_prefix('*').unwrap_or(pinned_path);
+        if pins_directory
+            .join(pinned_path)
+            .canonicalize()
+            .is_ok_and(|pinned_path| pinned_path == list_path)
+        {
+            return Ok(Some(sha256));
+        }
+    }
+    Ok(None)
+}
+
+/// Check that a SHA-256 hash given by the user is 64 hexadecimal characters, and lowercase it
+pub fn parse_sha256(sha256: &str) -> Result<String, String> {
+    if sha256.len() == 64 && sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(sha256.to_lowercase())
+    } else {
+        Err(format!(
+            "'{}' isn't a SHA-256 hash, which is 64 hexadecimal characters",
+            sha256
+        ))
+    }
+}
+
+/// SHA-256 hash of a file's contents, as `sha256sum` would print it
+pub fn sha256_of(contents: &[u8]) -> String {
+    format_hex(&Sha256::digest(contents))
+}
+
+/// Check that the contents of the word list file at the given path have the expected SHA-256
+/// hash. The contents are passed in, rather than read here, so that lists read from stdin can be
+/// checked too.
+pub fn check_list_sha256(
+    path: &Path,
+    contents: &[u8],
+    expected_sha256: &str,
+) -> Result<(), String> {
+    let sha256 = sha256_of(contents);
+    if sha256 == expected_sha256 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Word list {} has a SHA-256 of {}, but it's pinned to {}. It may have been changed, so Phraze won't use it",
+            if is_stdin(path) {
+                "from stdin".to_string()
+            } else {
+                path.display().to_string()
+            },
+            sha256,
+            expected_sha256
+        ))
+    }
+}
+
+#[test]
+fn can_pin_lists_in_pins_file() {
+    let base = env::temp_dir().join(format!("phraze-pinned-lists-test-{}", std::process::id()));
+    fs::create_dir_all(base.join("lists")).unwrap();
+    let list = base.join("lists").join("team.txt");
+    let other_list = base.join("lists").join("other.txt");
+    fs::write(&list, "apple\nbanana\ncherry\n").unwrap();
+    fs::write(&other_list, "apple\n").unwrap();
+    let sha256 = "6883ada0490e1bd845b6032e95119d522212f98b840eef466ebb09f4a9eb7a03";
+    assert_eq!(sha256_of(&fs::read(&list).unwrap()), sha256);
+
+    let pins_file = base.join("pinned-lists");
+    fs::write(
+        &pins_file,
+        format!("# Our lists\n{}  lists/team.txt\n", sha256),
+    )
+    .unwrap();
+    assert_eq!(
+        find_pinned_sha256(&list, &pins_file).unwrap().as_deref(),
+        Some(sha256)
+    );
+    assert_eq!(find_pinned_sha256(&other_list, &pins_file).unwrap(), None);
+    assert!(check_list_sha256(&list, &fs::read(&list).unwrap(), sha256).is_ok());
+    assert!(check_list_sha256(&other_list, &fs::read(&other_list).unwrap(), sha256).is_err());
+
+    fs::write(&pins_file, "not a hash  lists/team.txt\n").unwrap();
+    assert!(find_pinned_sha256(&list, &pins_file).is_err());
+
+    // If we can't tell whether a list is pinned, don't carry on as if it isn't
+    fs::write(&pins_file, b"\xff\xfe not UTF-8\n").unwrap();
+    assert!(find_pinned_sha256(&list, &pins_file).is_err());
+    fs::write(&pins_file, format!("{}  lists/team.txt\n", sha256)).unwrap();
+    assert!(find_pinned_sha256(&base.join("lists").join("missing.txt"), &pins_file).is_err());
+    assert_eq!(
+        find_pinned_sha256(Path::new("-"), &pins_file).unwrap(),
+        None
+    );
+    assert_eq!(
+        find_pinned_sha256(&list, &base.join("no-such-pins-file")).unwrap(),
+        None
+    );
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -0,0 +1,161 @@
+//! Presets bundle up settings for some common uses of a passphrase, so users don't have to work
+//! out the right entropy, separator and so on themselves. Each preset expands into the same
+//! settings a user could give on the command line (see `cli::Args::apply_preset`), and any of
+//! those the user does give win out over the preset's.
+use crate::charset::Charset;
+use crate::list_choice::ListChoice;
+use std::fmt;
+use std::str::FromStr;
+
+/// A use case Phraze has a preset for
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Wifi,
+    Disk,
+    Master,
+    SshKey,
+    PinPad,
+    Throwaway,
+}
+
+/// The settings a preset expands into
+#[derive(Clone, Debug, PartialEq)]
+pub struct PresetSettings {
+    /// Minimum entropy of the passphrase, in bits
+    pub minimum_entropy: usize,
+    pub separator: &'static str,
+    pub title_case: bool,
+    /// Word list to use, if the preset calls for a particular one. If the list isn't built into
+    /// this copy of Phraze, the usual default list is used.
+    pub list_choice: Option<ListChoice>,
+    /// Longest the passphrase may be, in characters
+    pub maximum_length: Option<usize>,
+    /// Shortest the passphrase may be, in characters
+    pub minimum_length: Option<usize>,
+    /// Characters the passphrase is allowed to use
+    pub charset: Option<Charset>,
+}
+
+impl Preset {
+    pub const ALL: &'static [Preset] = &[
+        Preset::Wifi,
+        Preset::Disk,
+        Preset::Master,
+        Preset::SshKey,
+        Preset::PinPad,
+        Preset::Throwaway,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Preset::Wifi => "wifi",
+            Preset::Disk => "disk",
+            Preset::Master => "master",
+            Preset::SshKey => "ssh-key",
+            Preset::PinPad => "pin-pad",
+            Preset::Throwaway => "throwaway",
+        }
+    }
+
+    /// A short description of what the preset is for and what it does, for help text
+    pub const fn description(self) -> &'static str {
+        match self {
+            Preset::Wifi => {
+                "Wi-Fi (WPA2) password: 80 bits, only printable ASCII, and 8 to 63 characters long"
+            }
+            Preset::Disk => {
+                "Disk encryption: 100 bits, with spaces between words for easy typing at boot"
+            }
+            Preset::Master => "Password manager master password: 120 bits",
+            Preset::SshKey => "SSH or GPG key passphrase: 100 bits",
+            Preset::PinPad => {
+                "Typed on an alphabetical on-screen keypad, like a TV's or game console's: 60 bits from the Alpha list, with no separators or capital letters to hunt for"
+            }
+            Preset::Throwaway => "Short-lived or low-stakes password: 50 bits",
+        }
+    }
+
+    pub fn settings(self) -> PresetSettings {
+        let defaults = PresetSettings {
+            minimum_entropy: 80,
+            separator: "-",
+            title_case: false,
+            list_choice: None,
+            maximum_length: None,
+            minimum_length: None,
+            charset: None,
+        };
+        match self {
+            // WPA2 passphrases must be 8 to 63 printable ASCII characters. 80 bits of words is
+            // well over 8 characters, but user can ask for less entropy or a list of short words.
+            Preset::Wifi => PresetSettings {
+                maximum_length: Some(63),
+                minimum_length: Some(8),
+                charset: Some(Charset::Ascii),
+                ..defaults
+            },
+            Preset::Disk => PresetSettings {
+                minimum_entropy: 100,
+                separator: " ",
+                ..defaults
+            },
+            Preset::Master => PresetSettings {
+                minimum_entropy: 120,
+                ..defaults
+            },
+            Preset::SshKey => PresetSettings {
+                minimum_entropy: 100,
+                ..defaults
+            },
+            // The Alpha list is uniquely decodable, so it's safe to leave out separators
+            Preset::PinPad => PresetSettings {
+                minimum_entropy: 60,
+                separator: "",
+                list_choice: "orchard-street-alpha".parse().ok(),
+                ..defaults
+            },
+            Preset::Throwaway => PresetSettings {
+                minimum_entropy: 50,
+                ..defaults
+            },
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .iter()
+            .find(|preset| preset.name() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "'{}' isn't one of Phraze's presets. Options are {}",
+                    s,
+                    Preset::ALL
+                        .iter()
+                        .map(|preset| preset.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+#[test]
+fn can_parse_and_display_presets() {
+    for preset in Preset::ALL {
+        assert_eq!(preset.to_string().parse::<Preset>(), Ok(*preset));
+    }
+    assert_eq!("SSH-Key".parse::<Preset>(), Ok(Preset::SshKey));
+    assert_eq!("pin-pad".parse::<Preset>(), Ok(Preset::PinPad));
+    assert!("bank".parse::<Preset>().is_err());
+}
@@ -0,0 +1,152 @@
+//! Some systems only allow short passwords, too short for a passphrase of real words. For those,
+//! Phraze can make up pronounceable pseudo-words, like "bafik", from a pattern of consonants and
+//! vowels. Each letter is picked from its own set, so the number of pseudo-words a pattern can
+//! make is exactly the product of the sizes of those sets, and so is the entropy: we don't have to
+//! estimate anything from a word list.
+//!
+//! Every pseudo-word from a pattern has the same length, so passphrases made from them can always
+//! be split back into pseudo-words, even without a separator.
+use std::fmt;
+use std::str::FromStr;
+
+/// Consonants pseudo-words are made from. Letters that are easily misheard or misread, or that
+/// don't have one obvious pronunciation (c, q, w, x and y), are left out.
+pub const CONSONANTS: &[char] = &[
+    'b', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'v', 'z',
+];
+
+/// Vowels pseudo-words are made from
+pub const VOWELS: &[char] = &['a', 'e', 'i', 'o', 'u'];
+
+/// The pattern Phraze uses when user doesn't give one
+pub const DEFAULT_PATTERN: &str = "cvcvc";
+
+/// The kind of letter at one spot of a pseudo-word
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Letter {
+    Consonant,
+    Vowel,
+}
+
+/// Which kind of letter goes at each spot of a pseudo-word, like "cvcvc"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyllablePattern {
+    pub letters: Vec<Letter>,
+}
+
+impl fmt::Display for SyllablePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for letter in &self.letters {
+            match letter {
+                Letter::Consonant => write!(f, "c")?,
+                Letter::Vowel => write!(f, "v")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SyllablePattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let letters = s
+            .chars()
+            .map(|c| match c.to_ascii_lowercase() {
+                'c' => Ok(Letter::Consonant),
+                'v' => Ok(Letter::Vowel),
+                _ => Err(format!(
+                    "Pseudo-word pattern '{}' can only have c (for a consonant) and v (for a vowel), like {}",
+                    s, DEFAULT_PATTERN
+                )),
+            })
+            .collect::<Result<Vec<Letter>, String>>()?;
+        if letters.is_empty() {
+            return Err(format!(
+                "Pseudo-word pattern needs at least one letter, like {}",
+                DEFAULT_PATTERN
+            ));
+        }
+        Ok(SyllablePattern { letters })
+    }
+}
+
+/// Everything needed to make pseudo-words: a pattern, and the letters to fill it in with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PseudoWords {
+    pub pattern: SyllablePattern,
+    pub consonants: Vec<char>,
+    pub vowels: Vec<char>,
+}
+
+impl PseudoWords {
+    pub fn new(pattern: SyllablePattern) -> Self {
+        PseudoWords {
+            pattern,
+            consonants: CONSONANTS.to_vec(),
+            vowels: VOWELS.to_vec(),
+        }
+    }
+
+    /// Leave out any letters that `keep` returns false for, such as letters that aren't in a
+    /// charset
+    pub fn keep_letters(&mut self, keep: impl Fn(char) -> bool) {
+        self.consonants.retain(|c| keep(*c));
+        self.vowels.retain(|c| keep(*c));
+    }
+
+    /// The letters that can go at a spot of the pattern
+    pub fn letters_for(&self, letter: Letter) -> &[char] {
+        match letter {
+            Letter::Consonant => &self.consonants,
+            Letter::Vowel => &self.vowels,
+        }
+    }
+
+    /// Exactly how many different pseudo-words can be made, or `None` if it's too many to count
+    pub fn number_of_pseudo_words(&self) -> Option<u128> {
+        self.pattern
+            .letters
+            .iter()
+            .try_fold(1u128, |count, letter| {
+                count.checked_mul(self.letters_for(*letter).len() as u128)
+            })
+    }
+
+    /// Entropy of one pseudo-word, in bits. Since every letter is picked independently and
+    /// uniformly, this is the sum of log2 of the number of letters at each spot.
+    pub fn bits_per_pseudo_word(&self) -> f64 {
+        self.pattern
+            .letters
+            .iter()
+            .map(|letter| (self.letters_for(*letter).len() as f64).log2())
+            .sum()
+    }
+
+    /// Length of every pseudo-word, in characters
+    pub fn length(&self) -> usize {
+        self.pattern.letters.len()
+    }
+}
+
+#[test]
+fn can_count_pseudo_words_exactly() {
+    let pseudo_words = PseudoWords::new("cvcvc".parse().unwrap());
+    assert_eq!(
+        pseudo_words.number_of_pseudo_words(),
+        Some(16 * 5 * 16 * 5 * 16)
+    );
+    assert_eq!(
+        pseudo_words.bits_per_pseudo_word(),
+        3.0 * 4.0 + 2.0 * 5f64.log2()
+    );
+    assert_eq!(pseudo_words.length(), 5);
+    assert_eq!(pseudo_words.pattern.to_string(), "cvcvc");
+
+    let mut pseudo_words = PseudoWords::new("CV".parse().unwrap());
+    pseudo_words.keep_letters(|c| c != 'a' && c != 'z');
+    assert_eq!(pseudo_words.number_of_pseudo_words(), Some(15 * 4));
+
+    assert!("cvx".parse::<SyllablePattern>().is_err());
+    assert!("".parse::<SyllablePattern>().is_err());
+}
@@ -0,0 +1,386 @@
+//! Functions for recovering a passphrase the user only half-remembers. Each word the user gives
+//! is compared against every word on the list using edit distance, and the closest matches are
+//! combined into a ranked list of candidate passphrases. A `?` stands in for a word the user
+//! can't remember at all.
+use crate::keyboard_layouts::KeyboardLayout;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use unicode_normalization::char::is_combining_mark;
+
+/// Costs are counted in half-edits, so that a typo of a neighboring key can count as half an
+/// edit while everything else counts as a whole one.
+const EDIT_COST: u32 = 2;
+const ADJACENT_KEY_COST: u32 = 1;
+
+/// A word from the list that might be what the user meant, and how far it is from what they typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub word_index: usize,
+    pub cost: u32,
+}
+
+/// Break what the user remembers of their passphrase into words (or "?"s), using any
+/// non-letters between them as separators. Also returns the first separator found, if any.
+///
+/// Some lists have words with non-letters in them, like "t-shirt" on the EFF list, so a
+/// separator that shows up inside a word on the list might be part of a word instead. For those,
+/// every way of splitting the pattern is returned, from the one that splits at every separator
+/// to the ones that join pieces back into words. Pieces are only joined into tokens with as many
+/// non-letter runs as the list's words have, so a list without such words gives a single split.
+pub fn split_pattern<T: AsRef<str>>(
+    pattern: &str,
+    list: &[T],
+) -> (Vec<Vec<String>>, Option<String>) {
+    let runs = split_into_runs(pattern.trim());
+    // Leading and trailing non-letters can't separate words, so leave them off
+    let first_word = runs.iter().position(|(is_word, _)| *is_word);
+    let last_word = runs.iter().rposition(|(is_word, _)| *is_word);
+    let runs = match (first_word, last_word) {
+        (Some(first), Some(last)) => &runs[first..=last],
+        _ => return (vec![], None),
+    };
+    // Runs alternate, so word pieces are at even positions and the gaps between them are at odd
+    // ones
+    let pieces: Vec<&str> = runs
+        .iter()
+        .step_by(2)
+        .map(|(_, run)| run.as_str())
+        .collect();
+    let gaps: Vec<&str> = runs
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .map(|(_, run)| run.as_str())
+        .collect();
+    let gap_is_in_a_word: Vec<bool> = gaps
+        .iter()
+        .map(|gap| list.iter().any(|word| word.as_ref().contains(gap)))
+        .collect();
+    let most_gaps_in_a_word = list
+        .iter()
+        .map(|word| {
+            split_into_runs(word.as_ref())
+                .iter()
+                .filter(|(is_word, _)| !is_word)
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+
+    // A gap that's never part of a word is surely the separator, so prefer it
+    let separator = gaps
+        .iter()
+        .zip(&gap_is_in_a_word)
+        .find(|(_, in_a_word)| !**in_a_word)
+        .map(|(gap, _)| gap)
+        .or(gaps.first())
+        .map(|gap| gap.to_string());
+    let splits = splits_from(0, &pieces, &gaps, &gap_is_in_a_word, most_gaps_in_a_word);
+    (splits, separator)
+}
+
+/// Break a string into runs of word characters (letters and "?"s) and runs of everything else
+fn split_into_runs(s: &str) -> Vec<(bool, String)> {
+    let mut runs: Vec<(bool, String)> = vec![];
+    for c in s.chars() {
+        let is_word_char = c.is_alphabetic() || is_combining_mark(c) || c == '?';
+        match runs.last_mut() {
+            Some((is_word, run)) if *is_word == is_word_char => run.push(c),
+            _ => runs.push((is_word_char, c.to_string())),
+        }
+    }
+    runs
+}
+
+/// Every way to split the pieces from `start` onwards into tokens, joining pieces back together
+/// across gaps that might be part of a word
+fn splits_from(
+    start: usize,
+    pieces: &[&str],
+    gaps: &[&str],
+    gap_is_in_a_word: &[bool],
+    most_gaps_in_a_word: usize,
+) -> Vec<Vec<String>> {
+    if start == pieces.len() {
+        return vec![vec![]];
+    }
+    let mut splits = vec![];
+    let mut token = pieces[start].to_string();
+    for end in start..pieces.len() {
+        if end > start {
+            // A "?" is a whole word the user can't remember, so never join one to anything
+            if end - start > most_gaps_in_a_word
+                || !gap_is_in_a_word[end - 1]
+                || pieces[end - 1] == "?"
+                || pieces[end] == "?"
+            {
+                break;
+            }
+            token.push_str(gaps[end - 1]);
+            token.push_str(pieces[end]);
+        }
+        for rest in splits_from(end + 1, pieces, gaps, gap_is_in_a_word, most_gaps_in_a_word) {
+            let mut split = vec![token.clone()];
+            split.extend(rest);
+            splits.push(split);
+        }
+    }
+    splits
+}
+
+/// Find the words on the list within `maximum_edits` edits of the given token, sorted from
+/// closest to furthest. A token of "?" matches every word on the list.
+/// If a `keyboard_layout` is given, mistyping a letter as one next to it on that keyboard only
+/// counts as half an edit.
+pub fn find_candidates<T: AsRef<str>>(
+    token: &str,
+    list: &[T],
+    maximum_edits: u32,
+    keyboard_layout: Option<KeyboardLayout>,
+) -> Vec<Candidate> {
+    if token == "?" {
+        return (0..list.len())
+            .map(|word_index| Candidate {
+                word_index,
+                cost: 0,
+            })
+            .collect();
+    }
+    let token: Vec<char> = token.to_lowercase().chars().collect();
+    let mut candidates: Vec<Candidate> = list
+        .iter()
+        .enumerate()
+        .filter_map(|(word_index, word)| {
+            let word: Vec<char> = word.as_ref().to_lowercase().chars().collect();
+            let cost = weighted_edit_distance(&token, &word, keyboard_layout);
+            (cost <= maximum_edits * EDIT_COST).then_some(Candidate { word_index, cost })
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| (candidate.cost, candidate.word_index));
+    candidates
+}
+
+/// Given the candidates for each word of the passphrase, for each way of splitting the pattern
+/// into words, return combinations of them, ranked from closest to what the user typed to
+/// furthest. Each combination is a list of word indexes. When two combinations are just as close,
+/// the one with fewer words comes first. Combinations are only worked out as they're needed, so
+/// take as many as you want.
+pub fn rank_combinations(
+    candidates_for_each_split: &[Vec<Vec<Candidate>>],
+) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let total_cost = |split: usize, positions: &[usize]| -> u32 {
+        positions
+            .iter()
+            .zip(&candidates_for_each_split[split])
+            .map(|(position, candidates)| candidates[*position].cost)
+            .sum()
+    };
+
+    // Since each word's candidates are sorted by cost, we can find combinations in order of total
+    // cost by starting with the best candidate for every word, then only ever moving one word
+    // to its next-best candidate at a time.
+    let mut queue = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    for (split, candidates_for_each_word) in candidates_for_each_split.iter().enumerate() {
+        if candidates_for_each_word.iter().any(|c| c.is_empty()) {
+            continue;
+        }
+        let start = vec![0; candidates_for_each_word.len()];
+        queue.push(Reverse((
+            total_cost(split, &start),
+            start.len(),
+            split,
+            start.clone(),
+        )));
+        seen.insert((split, start));
+    }
+    // Two splits can land on the same words, so only return each combination once
+    let mut returned = HashSet::new();
+    std::iter::from_fn(move || {
+        while let Some(Reverse((_cost, _length, split, positions))) = queue.pop() {
+            let candidates_for_each_word = &candidates_for_each_split[split];
+            for i in 0..positions.len() {
+                if positions[i] + 1 < candidates_for_each_word[i].len() {
+                    let mut next = positions.clone();
+                    next[i] += 1;
+                    if seen.insert((split, next.clone())) {
+                        queue.push(Reverse((total_cost(split, &next), next.len(), split, next)));
+                    }
+                }
+            }
+            let combination: Vec<usize> = positions
+                .iter()
+                .zip(candidates_for_each_word)
+                .map(|(position, candidates)| candidates[*position].word_index)
+                .collect();
+            if returned.insert(combination.clone()) {
+                return Some(combination);
+            }
+        }
+        None
+    })
+}
+
+/// Damerau-Levenshtein distance (optimal string alignment variant) between two words, counted in
+/// half-edits. Swapping two neighboring letters counts as one edit.
+fn weighted_edit_distance(a: &[char], b: &[char], keyboard_layout: Option<KeyboardLayout>) -> u32 {
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i as u32 * EDIT_COST;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j as u32 * EDIT_COST;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = if a[i - 1] == b[j - 1] {
+                0
+            } else if keyboard_layout.is_some_and(|layout| layout.are_adjacent(a[i - 1], b[j - 1]))
+            {
+                ADJACENT_KEY_COST
+            } else {
+                EDIT_COST
+            };
+            let mut distance = (distances[i - 1][j] + EDIT_COST)
+                .min(distances[i][j - 1] + EDIT_COST)
+                .min(distances[i - 1][j - 1] + substitution_cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + EDIT_COST);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[test]
+fn can_split_a_pattern_into_words_and_wildcards() {
+    let list = ["able", "horse", "house", "orange", "table"];
+    let (splits, separator) = split_pattern("tabel-orange-?-horse", &list);
+    assert_eq!(splits, vec![vec!["tabel", "orange", "?", "horse"]]);
+    assert_eq!(separator, Some("-".to_string()));
+    let (splits, separator) = split_pattern("tabel - ? - horse", &list);
+    assert_eq!(splits, vec![vec!["tabel", "?", "horse"]]);
+    assert_eq!(separator, Some(" - ".to_string()));
+    let (splits, separator) = split_pattern("tabel", &list);
+    assert_eq!(splits, vec![vec!["tabel"]]);
+    assert_eq!(separator, None);
+    let (splits, _separator) = split_pattern(" -- ", &list);
+    assert!(splits.is_empty());
+}
+
+#[test]
+fn can_split_a_pattern_with_separators_that_are_in_words() {
+    let list = ["drop-down", "felt-tip", "horse", "t-shirt"];
+    let (splits, separator) = split_pattern("t-shrit-horse", &list);
+    assert_eq!(
+        splits,
+        vec![
+            vec!["t", "shrit", "horse"],
+            vec!["t", "shrit-horse"],
+            vec!["t-shrit", "horse"],
+        ]
+    );
+    assert_eq!(separator, Some("-".to_string()));
+    // A separator that's in no word can't be part of one, so it's the separator
+    let (splits, separator) = split_pattern("t-shrit drop-down ?", &list);
+    assert_eq!(
+        splits,
+        vec![
+            vec!["t", "shrit", "drop", "down", "?"],
+            vec!["t", "shrit", "drop-down", "?"],
+            vec!["t-shrit", "drop", "down", "?"],
+            vec!["t-shrit", "drop-down", "?"],
+        ]
+    );
+    assert_eq!(separator, Some(" ".to_string()));
+}
+
+#[cfg(feature = "list-eff")]
+#[test]
+fn can_recover_a_passphrase_of_hyphenated_eff_words() {
+    use crate::fetch_list;
+    use crate::list_choice::ListChoice;
+    let list = fetch_list(ListChoice::Eff);
+    let (splits, separator) = split_pattern("t-shrit-drop-down", list);
+    let candidates_for_each_split: Vec<Vec<Vec<Candidate>>> = splits
+        .iter()
+        .map(|split| {
+            split
+                .iter()
+                .map(|token| find_candidates(token, list, 2, None))
+                .collect()
+        })
+        .collect();
+    let best: Vec<&str> = rank_combinations(&candidates_for_each_split)
+        .next()
+        .unwrap()
+        .iter()
+        .map(|index| list[*index])
+        .collect();
+    assert_eq!(best, vec!["t-shirt", "drop-down"]);
+    assert_eq!(separator, Some("-".to_string()));
+}
+
+#[test]
+fn can_find_close_words() {
+    let list = ["able", "horse", "house", "orange", "table"];
+    let candidates = find_candidates("tabel", &list, 2, None);
+    assert_eq!(candidates[0].word_index, 4);
+    // "able" is 2 edits away from "tabel"
+    assert!(candidates.iter().any(|c| c.word_index == 0));
+    assert_eq!(find_candidates("?", &list, 2, None).len(), list.len());
+    assert!(find_candidates("zzzzzzz", &list, 2, None).is_empty());
+}
+
+#[test]
+fn neighboring_keys_count_as_smaller_typos() {
+    let horse: Vec<char> = "horse".chars().collect();
+    // 'd' is next to 's' on a QWERTY keyboard; 'u' is not
+    let horde: Vec<char> = "horde".chars().collect();
+    let house: Vec<char> = "house".chars().collect();
+    assert_eq!(
+        weighted_edit_distance(&horse, &horde, Some(KeyboardLayout::Qwerty)),
+        1
+    );
+    assert_eq!(weighted_edit_distance(&horse, &horde, None), 2);
+    assert_eq!(
+        weighted_edit_distance(&horse, &house, Some(KeyboardLayout::Qwerty)),
+        2
+    );
+    let hrose: Vec<char> = "hrose".chars().collect();
+    assert_eq!(weighted_edit_distance(&horse, &hrose, None), 2);
+}
+
+#[test]
+fn can_rank_combinations_by_total_cost() {
+    let candidates = vec![
+        vec![
+            Candidate {
+                word_index: 4,
+                cost: 0,
+            },
+            Candidate {
+                word_index: 0,
+                cost: 2,
+            },
+        ],
+        vec![
+            Candidate {
+                word_index: 1,
+                cost: 1,
+            },
+            Candidate {
+                word_index: 2,
+                cost: 4,
+            },
+        ],
+    ];
+    let candidates = vec![candidates];
+    let combinations: Vec<Vec<usize>> = rank_combinations(&candidates).take(10).collect();
+    assert_eq!(
+        combinations,
+        vec![vec![4, 1], vec![0, 1], vec![4, 2], vec![0, 2]]
+    );
+    assert_eq!(rank_combinations(&candidates).take(1).count(), 1);
+}
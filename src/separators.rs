@@ -1,9 +1,13 @@
 //! This module contains some functions that help deal with the separating punction between words
 //! in a passphrase. Most of it handles cases where the user requests a random symbol or number or
 //! either.
+use crate::charset::Charset;
 use rand::prelude::*;
 use rand::seq::IndexedRandom;
 
+/// The symbols Phraze picks from when the user asks for random symbols as separators.
+pub const CHARSET: &[u8] = b"!@#$%&*(){}[]\\:;'<>?,./_-+=";
+
 #[derive(PartialEq)]
 enum SeparatorType {
     Number,
@@ -21,6 +25,74 @@ pub fn make_separator(rng: &mut impl Rng, sep: &str) -> String {
     }
 }
 
+/// Like `make_separator`, but random separators are only picked from characters in the given
+/// charset. Use `check_separator_fits_charset` first, to make sure there's something to pick.
+pub fn make_separator_in_charset(rng: &mut impl Rng, sep: &str, charset: &Charset) -> String {
+    let digits = digits_in_charset(charset);
+    let symbols = symbols_in_charset(charset);
+    let choices = match sep {
+        "_n" => digits,
+        "_s" => symbols,
+        // Numbers and symbols are still equally likely, unless the charset only has one of them
+        "_b" if digits.is_empty() => symbols,
+        "_b" if symbols.is_empty() => digits,
+        "_b" => {
+            if rng.random_bool(0.5) {
+                digits
+            } else {
+                symbols
+            }
+        }
+        _ => return sep.to_string(),
+    };
+    choices
+        .choose(rng)
+        .expect("Charset has no characters for this separator")
+        .to_string()
+}
+
+/// Check that the given separator can be written in the given charset. For random separators,
+/// that means the charset has at least one character of the right type to pick from.
+pub fn check_separator_fits_charset(sep: &str, charset: &Charset) -> Result<(), String> {
+    let digits = digits_in_charset(charset);
+    let symbols = symbols_in_charset(charset);
+    let fits = match sep {
+        "_n" => !digits.is_empty(),
+        "_s" => !symbols.is_empty(),
+        "_b" => !digits.is_empty() || !symbols.is_empty(),
+        _ => charset.can_write(sep),
+    };
+    if fits {
+        Ok(())
+    } else {
+        Err(format!(
+            "Separator '{}' can't be written in the {} charset",
+            sep, charset
+        ))
+    }
+}
+
+fn digits_in_charset(charset: &Charset) -> Vec<char> {
+    ('0'..='9').filter(|c| charset.contains(*c)).collect()
+}
+
+fn symbols_in_charset(charset: &Charset) -> Vec<char> {
+    CHARSET
+        .iter()
+        .map(|c| *c as char)
+        .filter(|c| charset.contains(*c))
+        .collect()
+}
+
+/// How many characters each separator will take up in a passphrase. Random separators are always
+/// a single character.
+pub fn separator_length(sep: &str) -> usize {
+    match sep {
+        "_n" | "_s" | "_b" => 1,
+        _ => sep.chars().count(),
+    }
+}
+
 /// Return either a random number or symbol. 50/50 chance!
 fn get_random_number_or_symbol(rng: &mut impl Rng) -> String {
     // Randomly choose which separator type to use
@@ -37,7 +109,6 @@ fn get_random_number_or_symbol(rng: &mut impl Rng) -> String {
 /// Pick a random symbol for a separator between words.
 fn get_random_symbol(rng: &mut impl Rng) -> String {
     // I could probably simplify this with a choose method
-    const CHARSET: &[u8] = b"!@#$%&*(){}[]\\:;'<>?,./_-+=";
     let idx = rng.random_range(0..CHARSET.len());
     (CHARSET[idx] as char).to_string()
 }
@@ -46,3 +117,34 @@ fn get_random_symbol(rng: &mut impl Rng) -> String {
 fn get_random_number(rng: &mut impl Rng) -> String {
     rng.random_range(0..=9).to_string()
 }
+
+/// Returns true if given character is one of the symbols Phraze uses as a random separator.
+pub fn is_separator_symbol(c: char) -> bool {
+    c.is_ascii() && CHARSET.contains(&(c as u8))
+}
+
+#[test]
+fn random_separators_stick_to_charset() {
+    let mut rng = rand::rng();
+    let charset: Charset = "custom:abc7#".parse().unwrap();
+    for _ in 0..100 {
+        assert_eq!(make_separator_in_charset(&mut rng, "_n", &charset), "7");
+        assert_eq!(make_separator_in_charset(&mut rng, "_s", &charset), "#");
+        let both = make_separator_in_charset(&mut rng, "_b", &charset);
+        assert!(both == "7" || both == "#");
+        assert!(Charset::Alnum.can_write(&make_separator_in_charset(
+            &mut rng,
+            "_b",
+            &Charset::Alnum
+        )));
+    }
+}
+
+#[test]
+fn can_tell_whether_separator_fits_charset() {
+    assert!(check_separator_fits_charset("_b", &Charset::Alnum).is_ok());
+    assert!(check_separator_fits_charset("_s", &Charset::Alnum).is_err());
+    assert!(check_separator_fits_charset("-", &Charset::Alnum).is_err());
+    assert!(check_separator_fits_charset("-", &Charset::Ascii).is_ok());
+    assert!(check_separator_fits_charset("", &Charset::Alnum).is_ok());
+}
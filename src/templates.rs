@@ -0,0 +1,147 @@
+//! Passphrases that read like a sentence, such as "brave-otter-juggles-quietly", are easier to
+//! remember than a string of unrelated words. A `Template` is a list of slots, each a part of
+//! speech, and each word of the passphrase is picked from the words on a tagged list with that
+//! slot's part of speech. Every slot is picked independently, so a passphrase's entropy is the
+//! sum of log2 of the number of words each slot could be.
+use std::fmt;
+use std::str::FromStr;
+
+/// A part of speech a word on a tagged list can have
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PartOfSpeech {
+    Adjective,
+    Noun,
+    Verb,
+    Adverb,
+}
+
+impl PartOfSpeech {
+    /// Every part of speech Phraze knows about
+    pub const ALL: &[PartOfSpeech] = &[
+        PartOfSpeech::Adjective,
+        PartOfSpeech::Noun,
+        PartOfSpeech::Verb,
+        PartOfSpeech::Adverb,
+    ];
+}
+
+impl fmt::Display for PartOfSpeech {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartOfSpeech::Adjective => write!(f, "adjective"),
+            PartOfSpeech::Noun => write!(f, "noun"),
+            PartOfSpeech::Verb => write!(f, "verb"),
+            PartOfSpeech::Adverb => write!(f, "adverb"),
+        }
+    }
+}
+
+impl FromStr for PartOfSpeech {
+    type Err = String;
+
+    /// Accepts full names, like "adjective", or short ones, like "adj"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_ref() {
+            "adjective" | "adj" | "a" => Ok(PartOfSpeech::Adjective),
+            "noun" | "n" => Ok(PartOfSpeech::Noun),
+            "verb" | "v" => Ok(PartOfSpeech::Verb),
+            "adverb" | "adv" | "r" => Ok(PartOfSpeech::Adverb),
+            _ => Err(format!(
+                "'{}' isn't a part of speech Phraze knows about. Options are adjective (adj), noun (n), verb (v) and adverb (adv)",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// The parts of speech of each word of a passphrase, in order. If a passphrase needs more words
+/// than the template has slots, the template starts over from its first slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    pub slots: Vec<PartOfSpeech>,
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let slots: Vec<String> = self.slots.iter().map(|slot| slot.to_string()).collect();
+        write!(f, "{}", slots.join(" "))
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    /// Slots can be separated by spaces, commas or hyphens: "adj noun verb adv",
+    /// "adj,noun,verb,adv" and "adj-noun-verb-adv" are the same template.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let slots = s
+            .split([' ', ',', '-'])
+            .filter(|slot| !slot.trim().is_empty())
+            .map(|slot| slot.parse())
+            .collect::<Result<Vec<PartOfSpeech>, String>>()?;
+        if slots.is_empty() {
+            return Err("Template needs at least one part of speech, like 'adj noun'".to_string());
+        }
+        Ok(Template { slots })
+    }
+}
+
+/// A word list where each word is tagged with one or more parts of speech
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaggedList {
+    pub adjectives: Vec<String>,
+    pub nouns: Vec<String>,
+    pub verbs: Vec<String>,
+    pub adverbs: Vec<String>,
+}
+
+impl TaggedList {
+    /// Words on the list with the given part of speech
+    pub fn words(&self, part_of_speech: PartOfSpeech) -> &[String] {
+        match part_of_speech {
+            PartOfSpeech::Adjective => &self.adjectives,
+            PartOfSpeech::Noun => &self.nouns,
+            PartOfSpeech::Verb => &self.verbs,
+            PartOfSpeech::Adverb => &self.adverbs,
+        }
+    }
+
+    pub fn words_mut(&mut self, part_of_speech: PartOfSpeech) -> &mut Vec<String> {
+        match part_of_speech {
+            PartOfSpeech::Adjective => &mut self.adjectives,
+            PartOfSpeech::Noun => &mut self.nouns,
+            PartOfSpeech::Verb => &mut self.verbs,
+            PartOfSpeech::Adverb => &mut self.adverbs,
+        }
+    }
+
+    /// Every word on the list, whatever its part of speech, without duplicates
+    pub fn all_words(&self) -> Vec<String> {
+        let mut all_words: Vec<String> = PartOfSpeech::ALL
+            .iter()
+            .flat_map(|part_of_speech| self.words(*part_of_speech).iter().cloned())
+            .collect();
+        all_words.sort();
+        all_words.dedup();
+        all_words
+    }
+}
+
+#[test]
+fn can_parse_templates() {
+    let template = "adj noun verb adv".parse::<Template>().unwrap();
+    assert_eq!(
+        template.slots,
+        vec![
+            PartOfSpeech::Adjective,
+            PartOfSpeech::Noun,
+            PartOfSpeech::Verb,
+            PartOfSpeech::Adverb
+        ]
+    );
+    assert_eq!("adjective,noun,verb,adverb".parse(), Ok(template.clone()));
+    assert_eq!("a-n-v-r".parse(), Ok(template.clone()));
+    assert_eq!(template.to_string(), "adjective noun verb adverb");
+    assert!("adj pronoun".parse::<Template>().is_err());
+    assert!(" ".parse::<Template>().is_err());
+}
@@ -4,12 +4,92 @@
 //! word list will cause Phraze to OVER-estimate passphrase entropy strength, exactly what we do
 //! not want to happen.
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 use unicode_normalization::IsNormalized;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::is_nfc;
 use unicode_normalization::is_nfc_quick;
+use unicode_normalization::is_nfd;
 use unicode_normalization::is_nfd_quick;
+use unicode_normalization::is_nfkc;
 use unicode_normalization::is_nfkc_quick;
+use unicode_normalization::is_nfkd;
 use unicode_normalization::is_nfkd_quick;
 
+/// One of the 4 Unicode normalization forms
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Returns true if given string is already in this normalization form
+    pub fn is_normalized(self, s: &str) -> bool {
+        match self {
+            NormalizationForm::Nfc => is_nfc(s),
+            NormalizationForm::Nfd => is_nfd(s),
+            NormalizationForm::Nfkc => is_nfkc(s),
+            NormalizationForm::Nfkd => is_nfkd(s),
+        }
+    }
+
+    /// Put given string into this normalization form
+    pub fn normalize(self, s: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => s.nfc().collect(),
+            NormalizationForm::Nfd => s.nfd().collect(),
+            NormalizationForm::Nfkc => s.nfkc().collect(),
+            NormalizationForm::Nfkd => s.nfkd().collect(),
+        }
+    }
+}
+
+impl fmt::Display for NormalizationForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NormalizationForm::Nfc => write!(f, "NFC"),
+            NormalizationForm::Nfd => write!(f, "NFD"),
+            NormalizationForm::Nfkc => write!(f, "NFKC"),
+            NormalizationForm::Nfkd => write!(f, "NFKD"),
+        }
+    }
+}
+
+impl FromStr for NormalizationForm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_ref() {
+            "NFC" => Ok(NormalizationForm::Nfc),
+            "NFD" => Ok(NormalizationForm::Nfd),
+            "NFKC" => Ok(NormalizationForm::Nfkc),
+            "NFKD" => Ok(NormalizationForm::Nfkd),
+            _ => Err(format!(
+                "'{}' isn't a Unicode normalization form. Options are NFC, NFD, NFKC and NFKD",
+                s
+            )),
+        }
+    }
+}
+
+/// Find a normalization form that every word on the list is in, if there is one. Plenty of words
+/// (all plain ASCII words, for example) are in every form at once, so this prefers NFC, then NFD,
+/// then NFKC, then NFKD.
+pub fn detect_normalization_form<T: AsRef<str>>(list: &[T]) -> Option<NormalizationForm> {
+    [
+        NormalizationForm::Nfc,
+        NormalizationForm::Nfd,
+        NormalizationForm::Nfkc,
+        NormalizationForm::Nfkd,
+    ]
+    .into_iter()
+    .find(|form| list.iter().all(|word| form.is_normalized(word.as_ref())))
+}
+
 /// Given a slice of Strings, this function will attempt to detect the Unicode normalization used
 /// in each String.
 /// There are 4 different Unicode normalizations: NFC, NFD, NFKC, NFKD. Which ever one lists uses
@@ -65,3 +145,19 @@ fn can_detect_non_uniform_unicode_normalization_in_a_given_list() {
     // Should still be detected as uniform
     assert!(uniform_unicode_normalization(&uniform_list2));
 }
+
+#[test]
+fn can_detect_normalization_form_of_a_list() {
+    let nfc = "sécréter";
+    let nfd: String = nfc.nfd().collect();
+    assert_eq!(
+        detect_normalization_form(&["alpha", nfc]),
+        Some(NormalizationForm::Nfc)
+    );
+    assert_eq!(
+        detect_normalization_form(&["alpha", &nfd]),
+        Some(NormalizationForm::Nfd)
+    );
+    assert_eq!(detect_normalization_form(&[nfc, &nfd]), None);
+    assert_eq!(NormalizationForm::Nfd.normalize(nfc), nfd);
+}
@@ -0,0 +1,105 @@
+//! Users can keep word lists of their own in a couple of directories, then choose them by name
+//! with `--list`, as if they were built in. A list named "team" would be a file called `team.txt`
+//! in one of these directories:
+//!
+//! 1. `$XDG_DATA_HOME/phraze/lists`, which defaults to `~/.local/share/phraze/lists`
+//! 2. Each directory in `$PHRAZE_LIST_PATH`, which is separated like `$PATH`
+//!
+//! If more than one directory has a list with the same name, the first one wins.
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// The directories Phraze looks in for user word lists, in order of precedence
+pub fn word_list_directories() -> Vec<PathBuf> {
+    let mut directories = vec![];
+    let data_home = match env::var_os("XDG_DATA_HOME") {
+        Some(data_home) if !data_home.is_empty() => Some(PathBuf::from(data_home)),
+        _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")),
+    };
+    if let Some(data_home) = data_home {
+        directories.push(data_home.join("phraze").join("lists"));
+    }
+    if let Some(list_path) = env::var_os("PHRAZE_LIST_PATH") {
+        directories.extend(env::split_paths(&list_path).filter(|d| !d.as_os_str().is_empty()));
+    }
+    directories
+}
+
+/// Find the file for the user word list with the given name, searching the given directories
+/// in order.
+pub fn find_user_list(name: &str, directories: &[PathBuf]) -> Option<PathBuf> {
+    directories
+        .iter()
+        .map(|directory| directory.join(format!("{}.txt", name)))
+        .find(|path| path.is_file())
+}
+
+/// Every user word list found in the given directories, as (name, path) pairs, sorted by name.
+/// Where two directories have a list of the same name, only the one that takes precedence is
+/// included.
+pub fn find_all_user_lists(directories: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut lists: Vec<(String, PathBuf)> = vec![];
+    for directory in directories {
+        for (name, path) in lists_in_directory(directory) {
+            if !lists
+                .iter()
+                .any(|(existing_name, _)| *existing_name == name)
+            {
+                lists.push((name, path));
+            }
+        }
+    }
+    lists.sort();
+    lists
+}
+
+/// Every `.txt` file in the given directory, as (name, path) pairs. A directory that doesn't
+/// exist simply has no lists in it.
+fn lists_in_directory(directory: &Path) -> Vec<(String, PathBuf)> {
+    let entries = match directory.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "txt"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some((name, path))
+        })
+        .collect()
+}
+
+#[test]
+fn can_find_user_lists_by_name_in_order_of_precedence() {
+    use std::fs;
+    let base = env::temp_dir().join(format!("phraze-user-lists-test-{}", std::process::id()));
+    let first = base.join("first");
+    let second = base.join("second");
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+    fs::write(first.join("team.txt"), "alpha\nbeta\n").unwrap();
+    fs::write(second.join("team.txt"), "gamma\ndelta\n").unwrap();
+    fs::write(second.join("shared.txt"), "epsilon\nzeta\n").unwrap();
+    fs::write(second.join("notes.md"), "not a list").unwrap();
+    let directories = vec![first.clone(), second.clone(), base.join("missing")];
+
+    assert_eq!(
+        find_user_list("team", &directories),
+        Some(first.join("team.txt"))
+    );
+    assert_eq!(
+        find_user_list("shared", &directories),
+        Some(second.join("shared.txt"))
+    );
+    assert_eq!(find_user_list("notes", &directories), None);
+    assert_eq!(
+        find_all_user_lists(&directories),
+        vec![
+            ("shared".to_string(), second.join("shared.txt")),
+            ("team".to_string(), first.join("team.txt")),
+        ]
+    );
+
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "cli")]
+mod conflicting_option_tests {
+    use std::process::{Command, Output, Stdio};
+
+    fn run_phraze(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_phraze"))
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .expect("phraze binary runs")
+    }
+
+    /// Check that phraze refuses the given arguments, naming the given option in its error
+    fn assert_rejected(args: &[&str], option: &str) {
+        let output = run_phraze(args);
+        assert!(
+            !output.status.success(),
+            "phraze {} should have failed",
+            args.join(" ")
+        );
+        assert!(String::from_utf8_lossy(&output.stderr).contains(option));
+    }
+
+    #[test]
+    fn pseudo_words_are_not_picked_from_a_list() {
+        assert!(run_phraze(&["--pseudo-words"]).status.success());
+        assert_rejected(&["--pseudo-words", "-l", "m"], "-l");
+        assert_rejected(&["--pseudo-words", "-v", "--list", "m"], "-l");
+        assert_rejected(&["--pseudo-words", "-c", "Cargo.toml"], "--custom-list");
+    }
+
+    #[test]
+    fn passwords_have_no_words_to_separate_title_case_or_pick_from_a_list() {
+        assert!(run_phraze(&["--chars"]).status.success());
+        assert!(
+            run_phraze(&["--chars", "--preset", "wifi"])
+                .status
+                .success()
+        );
+        assert_rejected(&["--chars", "-s", "_"], "-s");
+        assert_rejected(&["--chars", "-t"], "-t");
+        assert_rejected(&["--chars", "-l", "m"], "-l");
+    }
+}
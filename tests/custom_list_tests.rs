@@ -0,0 +1,205 @@
+mod custom_list_tests {
+    use phraze::file_reader::*;
+    use phraze::templates::TaggedList;
+    use phraze::unicode_normalization_check::NormalizationForm;
+    use std::path::Path;
+
+    /// Parse the text of a word list file, leaving out any weights
+    fn parse_custom_list(text: &str) -> Result<(Vec<String>, ListHeader), String> {
+        parse_weighted_list(text).map(|(word_list, _weights, header)| (word_list, header))
+    }
+
+    fn parse_weighted_list(text: &str) -> Result<WeightedList, String> {
+        parse_list_file(Path::new("list.txt"), text.as_bytes())
+    }
+
+    fn parse_tagged_list(text: &str) -> Result<(TaggedList, ListHeader), String> {
+        parse_tagged_list_file(Path::new("list.txt"), text.as_bytes())
+    }
+
+    // SHA-256 of "apple\nbanana\ncherry\n"
+    const FRUIT_SHA256: &str = "6883ada0490e1bd845b6032e95119d522212f98b840eef466ebb09f4a9eb7a03";
+
+    #[test]
+    fn comments_are_not_words() {
+        let (word_list, header) =
+            parse_custom_list("# My list\napple\n# a comment\nbanana\n  # indented\n").unwrap();
+        assert_eq!(word_list, vec!["apple", "banana"]);
+        assert_eq!(header, ListHeader::default());
+    }
+
+    #[test]
+    fn can_strip_byte_order_mark_and_carriage_returns() {
+        let (word_list, _) = parse_custom_list("\u{feff}apple\r\nbanana\r\n").unwrap();
+        assert_eq!(word_list, vec!["apple", "banana"]);
+        let (word_list, _) = parse_custom_list("\u{feff}# name: Fruit\r\napple\r\n").unwrap();
+        assert_eq!(word_list, vec!["apple"]);
+    }
+
+    #[test]
+    fn can_read_header() {
+        let file = format!(
+            "# name: Fruit\n# expected-count: 3\n# normalization: NFC\n# sha256: {}\n# language: en\n# license: CC0-1.0\n# uniquely-decodable: true\n# Just a comment\napple\nbanana\ncherry\n",
+            FRUIT_SHA256
+        );
+        let (word_list, header) = parse_custom_list(&file).unwrap();
+        assert_eq!(word_list.len(), 3);
+        assert_eq!(header.name.as_deref(), Some("Fruit"));
+        assert_eq!(header.expected_count, Some(3));
+        assert_eq!(header.normalization, Some(NormalizationForm::Nfc));
+        assert_eq!(header.sha256.as_deref(), Some(FRUIT_SHA256));
+        assert_eq!(header.language.as_deref(), Some("en"));
+        assert_eq!(header.license.as_deref(), Some("CC0-1.0"));
+        assert_eq!(header.uniquely_decodable, Some(true));
+    }
+
+    #[test]
+    fn line_endings_and_comments_do_not_change_hash() {
+        let file = format!(
+            "\u{feff}# sha256: {}\r\napple\r\n# comment\r\nbanana\r\n\r\ncherry\r\n",
+            FRUIT_SHA256
+        );
+        assert!(parse_custom_list(&file).is_ok());
+    }
+
+    #[test]
+    fn lists_that_do_not_match_header_are_rejected() {
+        // A truncated download
+        assert!(parse_custom_list("# expected-count: 3\napple\nbanana\n").is_err());
+        let file = format!("# sha256: {}\napple\nbanana\n", FRUIT_SHA256);
+        assert!(parse_custom_list(&file).is_err());
+        assert!(parse_custom_list("# normalization: NFD\ncafé\n").is_err());
+        assert!(parse_custom_list("# uniquely-decodable: true\nback\nbackup\nup\n").is_err());
+        // Invalid values
+        assert!(parse_custom_list("# expected-count: lots\napple\n").is_err());
+        assert!(parse_custom_list("# normalization: NFX\napple\n").is_err());
+    }
+
+    #[test]
+    fn header_fields_after_first_word_are_just_comments() {
+        let (word_list, header) =
+            parse_custom_list("apple\n# expected-count: 10\nbanana\n").unwrap();
+        assert_eq!(word_list.len(), 2);
+        assert_eq!(header.expected_count, None);
+    }
+
+    #[test]
+    fn can_read_weighted_lists() {
+        let (word_list, weights, header) = parse_weighted_list(
+            "# weighted: true\nbanana\t1.5\napple\t3\n  cherry \t 1\napple\t1\n",
+        )
+        .unwrap();
+        assert_eq!(header.weighted, Some(true));
+        assert_eq!(word_list, vec!["apple", "banana", "cherry"]);
+        // Weights of a word that's on the list twice are added together
+        assert_eq!(weights, Some(vec![4.0, 1.5, 1.0]));
+        // Weights are left out when reading the list as a plain list
+        let (word_list, _) = parse_custom_list("# weighted: true\napple\t3\nbanana\t1\n").unwrap();
+        assert_eq!(word_list, vec!["apple", "banana"]);
+
+        let (_, weights, _) = parse_weighted_list("apple\nbanana\n").unwrap();
+        assert_eq!(weights, None);
+    }
+
+    #[test]
+    fn tabs_on_lists_without_weighted_header_are_part_of_the_line() {
+        let (word_list, weights, _) = parse_weighted_list("apple\t3\nbanana\n").unwrap();
+        assert_eq!(word_list, vec!["apple\t3", "banana"]);
+        assert_eq!(weights, None);
+        let (word_list, weights, _) =
+            parse_weighted_list("# weighted: false\nice cream\tsundae\n").unwrap();
+        assert_eq!(word_list, vec!["ice cream\tsundae"]);
+        assert_eq!(weights, None);
+        assert!(parse_weighted_list("# weighted: maybe\napple\n").is_err());
+    }
+
+    #[test]
+    fn weights_must_be_positive_numbers_on_every_line() {
+        for lines in [
+            "apple\t3\nbanana\n",
+            "apple\t0\nbanana\t1\n",
+            "apple\t-2\nbanana\t1\n",
+            "apple\tlots\nbanana\t1\n",
+            "apple\tinf\nbanana\t1\n",
+        ] {
+            assert!(parse_weighted_list(&format!("# weighted: true\n{}", lines)).is_err());
+        }
+    }
+
+    #[test]
+    fn can_read_tagged_lists() {
+        let (tagged_list, _) = parse_tagged_list("# name: Tagged\nbrave\tadj\nlight\tadjective,noun\notter\tnoun\notter\tn\njuggles\tverb\n",
+        )
+        .unwrap();
+        assert_eq!(tagged_list.adjectives, vec!["brave", "light"]);
+        assert_eq!(tagged_list.nouns, vec!["light", "otter"]);
+        assert_eq!(tagged_list.verbs, vec!["juggles"]);
+        assert!(tagged_list.adverbs.is_empty());
+        assert_eq!(
+            tagged_list.all_words(),
+            vec!["brave", "juggles", "light", "otter"]
+        );
+        // Header's expected count is of different words, whatever their parts of speech
+        assert!(parse_tagged_list("# expected-count: 2\nlight\tadj,noun\nfox\tn\n").is_ok());
+
+        assert!(parse_tagged_list("brave\tadj\notter\n").is_err());
+        assert!(parse_tagged_list("brave\tpronoun\n").is_err());
+    }
+
+    #[test]
+    fn can_merge_lists_without_duplicates() {
+        let nfc = "café".to_string();
+        let nfd = "cafe\u{301}".to_string();
+        let first = vec!["apple".to_string(), nfc.clone()];
+        let second = vec!["apple".to_string(), "banana".to_string(), nfd];
+        // Mixed normalization forms are merged as NFC
+        assert_eq!(
+            merge_word_lists(&[first.clone(), second]),
+            vec!["apple", "banana", "café"]
+        );
+        assert_eq!(merge_word_lists(&[first]), vec!["apple", "café"]);
+    }
+
+    const FRUIT: &[u8] = b"apple\nbanana\ncherry\n";
+
+    #[test]
+    fn uncompressed_files_are_read_as_is() {
+        let (word_list, _, _) = parse_list_file(Path::new("fruit.txt"), FRUIT).unwrap();
+        assert_eq!(word_list, vec!["apple", "banana", "cherry"]);
+        assert!(parse_list_file(Path::new("fruit.txt"), b"caf\xe9\n").is_err());
+        assert!(is_stdin(Path::new("-")));
+        assert!(!is_stdin(Path::new("fruit.txt")));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn can_read_gzipped_lists() {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(FRUIT).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let (word_list, _, _) = parse_list_file(Path::new("fruit.txt.gz"), &compressed).unwrap();
+        assert_eq!(word_list, vec!["apple", "banana", "cherry"]);
+        assert!(parse_list_file(Path::new("fruit.txt.gz"), FRUIT).is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn can_read_zstd_compressed_lists() {
+        let compressed =
+            ruzstd::encoding::compress_to_vec(FRUIT, ruzstd::encoding::CompressionLevel::Fastest);
+        let (word_list, _, _) = parse_list_file(Path::new("fruit.txt.zst"), &compressed).unwrap();
+        assert_eq!(word_list, vec!["apple", "banana", "cherry"]);
+        assert!(parse_list_file(Path::new("fruit.txt.zst"), FRUIT).is_err());
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn can_read_xz_compressed_lists() {
+        let mut compressed = vec![];
+        lzma_rs::xz_compress(&mut std::io::BufReader::new(FRUIT), &mut compressed).unwrap();
+        let (word_list, _, _) = parse_list_file(Path::new("fruit.txt.xz"), &compressed).unwrap();
+        assert_eq!(word_list, vec!["apple", "banana", "cherry"]);
+        assert!(parse_list_file(Path::new("fruit.txt.xz"), FRUIT).is_err());
+    }
+}
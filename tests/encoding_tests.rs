@@ -0,0 +1,105 @@
+#![cfg(feature = "cli")]
+mod encoding_tests {
+    #[cfg(feature = "list-eff")]
+    use phraze::fetch_list;
+    use phraze::list_choice::ListChoice;
+    #[cfg(feature = "list-eff")]
+    use phraze::mnemonic_encoding::decode_word_indexes;
+    use rand::SeedableRng;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Run the phraze binary with the given arguments, feeding it the given bytes on stdin, and
+    /// return what it writes to stdout
+    fn run_phraze(args: &[&str], stdin: &[u8]) -> Vec<u8> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_phraze"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("phraze binary runs");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin)
+            .expect("can write to phraze's stdin");
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "phraze {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output.stdout
+    }
+
+    /// Encode the bytes as words, then decode the words, through the command line
+    fn round_trip(bytes: &[u8], list_code: &str) -> Vec<u8> {
+        let words = run_phraze(&["encode", "-l", list_code], bytes);
+        run_phraze(&["decode", "-l", list_code], &words)
+    }
+
+    #[test]
+    fn bytes_survive_a_round_trip_through_every_built_in_list() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for list_choice in ListChoice::ALL {
+            for length in [1, 2, 7, 32] {
+                let bytes: Vec<u8> = (0..length).map(|_| rng.random()).collect();
+                assert_eq!(round_trip(&bytes, list_choice.code()), bytes);
+            }
+        }
+    }
+
+    #[cfg(feature = "list-eff")]
+    #[test]
+    fn bytes_encoded_as_hyphenated_eff_words_survive_a_round_trip() {
+        assert_eq!(
+            run_phraze(&["encode", "-l", "e"], &[0x19, 0xef]),
+            b"t-shirt\n"
+        );
+
+        let list = fetch_list(ListChoice::Eff);
+        let index = |word: &str| list.iter().position(|w| *w == word).unwrap();
+        let words = ["t-shirt", "drop-down", "felt-tip", "yo-yo", "t-shirt"];
+        let word_indexes: Vec<usize> = words.iter().map(|word| index(word)).collect();
+        let bytes = decode_word_indexes(&word_indexes, list.len());
+        let encoded = run_phraze(&["encode", "-l", "e"], &bytes);
+        assert_eq!(encoded, format!("{}\n", words.join("-")).as_bytes());
+        assert_eq!(run_phraze(&["decode", "-l", "e"], &encoded), bytes);
+    }
+
+    #[test]
+    fn leading_zero_bytes_survive_a_round_trip() {
+        for list_choice in ListChoice::ALL {
+            for bytes in [vec![0], vec![0, 0, 0], vec![0, 0, 1, 255], vec![0, 255, 0]] {
+                assert_eq!(round_trip(&bytes, list_choice.code()), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn cannot_read_word_list_and_input_from_stdin() {
+        for subcommand in ["encode", "decode"] {
+            let output = Command::new(env!("CARGO_BIN_EXE_phraze"))
+                .args([subcommand, "-c", "-"])
+                .stdin(Stdio::null())
+                .output()
+                .unwrap();
+            assert!(!output.status.success());
+            assert!(String::from_utf8_lossy(&output.stderr).contains("stdin"));
+        }
+    }
+
+    #[test]
+    fn empty_input_survives_a_round_trip() {
+        for list_choice in ListChoice::ALL {
+            let words = run_phraze(&["encode", "-l", list_choice.code()], &[]);
+            assert_eq!(words, b"\n");
+            assert!(run_phraze(&["decode", "-l", list_choice.code()], &words).is_empty());
+        }
+    }
+}
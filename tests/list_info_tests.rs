@@ -0,0 +1,31 @@
+mod list_info_tests {
+    use phraze::list_info::*;
+    use phraze::*;
+
+    // These should match the "Technical details" section of the README
+    #[test]
+    fn can_calculate_stats_of_built_in_lists() {
+        for built_in_list in BUILT_IN_LISTS {
+            let stats = ListStats::from_list(fetch_list(built_in_list.list_choice));
+            assert!(stats.uniquely_decodable);
+            let expected_prefix = match built_in_list.list_choice.code() {
+                "m" => 10,
+                "l" => 15,
+                "e" => 9,
+                "s" => 5,
+                _ => 7,
+            };
+            assert_eq!(stats.shortest_unique_prefix, expected_prefix);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "list-medium")]
+    fn medium_list_needs_seven_words_for_eighty_bits() {
+        use phraze::list_choice::ListChoice;
+        let stats = ListStats::from_list(fetch_list(ListChoice::Medium));
+        assert_eq!(stats.word_count, 8192);
+        assert_eq!(stats.bits_per_word, 13.0);
+        assert_eq!(stats.words_for_default_entropy, Some(7));
+    }
+}
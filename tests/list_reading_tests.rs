@@ -1,60 +1,112 @@
 mod minimum_entropy_tests {
-    use phraze::cli::ListChoice;
+    use phraze::list_choice::ListChoice;
     use phraze::*;
 
     #[test]
     fn can_read_in_lists_and_find_appropriate_number_of_words() {
-        let list = fetch_list(ListChoice::Medium);
-        assert!(list.len() == 8192);
+        #[cfg(feature = "list-medium")]
+        {
+            let list = fetch_list(ListChoice::Medium);
+            assert!(list.len() == 8192);
+        }
 
-        let list = fetch_list(ListChoice::Long);
-        assert!(list.len() == 17576);
+        #[cfg(feature = "list-long")]
+        {
+            let list = fetch_list(ListChoice::Long);
+            assert!(list.len() == 17576);
+        }
 
-        let list = fetch_list(ListChoice::Qwerty);
-        assert!(list.len() == 1296);
+        #[cfg(feature = "list-qwerty")]
+        {
+            let list = fetch_list(ListChoice::Qwerty);
+            assert!(list.len() == 1296);
+        }
 
-        let list = fetch_list(ListChoice::Alpha);
-        assert!(list.len() == 1296);
+        #[cfg(feature = "list-alpha")]
+        {
+            let list = fetch_list(ListChoice::Alpha);
+            assert!(list.len() == 1296);
+        }
 
-        let list = fetch_list(ListChoice::Eff);
-        assert!(list.len() == 7776);
+        #[cfg(feature = "list-eff")]
+        {
+            let list = fetch_list(ListChoice::Eff);
+            assert!(list.len() == 7776);
+        }
 
-        let list = fetch_list(ListChoice::Effshort);
-        assert!(list.len() == 1296);
+        #[cfg(feature = "list-eff-short")]
+        {
+            let list = fetch_list(ListChoice::Effshort);
+            assert!(list.len() == 1296);
+        }
 
-        let list = fetch_list(ListChoice::Mnemonicode);
-        assert!(list.len() == 1633);
+        #[cfg(feature = "list-mnemonicode")]
+        {
+            let list = fetch_list(ListChoice::Mnemonicode);
+            assert!(list.len() == 1633);
+        }
     }
 
     #[test]
     fn can_read_in_lists_without_any_blank_words() {
-        let list = fetch_list(ListChoice::Medium);
-        assert!(!list.contains(&""));
-        assert!(!list.contains(&"\n"));
-        assert!(list.contains(&"abbey"));
-
-        let list = fetch_list(ListChoice::Long);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
-
-        let list = fetch_list(ListChoice::Qwerty);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
-
-        let list = fetch_list(ListChoice::Alpha);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
-
-        let list = fetch_list(ListChoice::Eff);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
-
-        let list = fetch_list(ListChoice::Effshort);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
-
-        let list = fetch_list(ListChoice::Mnemonicode);
-        assert!(!list.contains(&"\n"));
-        assert!(!list.contains(&""));
+        #[cfg(feature = "list-medium")]
+        {
+            let list = fetch_list(ListChoice::Medium);
+            assert!(!list.contains(&""));
+            assert!(!list.contains(&"\n"));
+            assert!(list.contains(&"abbey"));
+        }
+
+        #[cfg(feature = "list-long")]
+        {
+            let list = fetch_list(ListChoice::Long);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+
+        #[cfg(feature = "list-qwerty")]
+        {
+            let list = fetch_list(ListChoice::Qwerty);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+
+        #[cfg(feature = "list-alpha")]
+        {
+            let list = fetch_list(ListChoice::Alpha);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+
+        #[cfg(feature = "list-eff")]
+        {
+            let list = fetch_list(ListChoice::Eff);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+
+        #[cfg(feature = "list-eff-short")]
+        {
+            let list = fetch_list(ListChoice::Effshort);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+
+        #[cfg(feature = "list-mnemonicode")]
+        {
+            let list = fetch_list(ListChoice::Mnemonicode);
+            assert!(!list.contains(&"\n"));
+            assert!(!list.contains(&""));
+        }
+    }
+
+    #[test]
+    fn metadata_from_build_script_matches_lists() {
+        for list_choice in ListChoice::ALL {
+            let list = fetch_list(*list_choice);
+            let metadata = list_metadata(*list_choice);
+            assert_eq!(metadata.word_count, list.len());
+            assert_eq!(metadata.bits_per_word, (list.len() as f64).log2());
+        }
     }
 }
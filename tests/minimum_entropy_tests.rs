@@ -4,23 +4,29 @@ mod minimum_entropy_tests {
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_desired_number_of_words()
      {
-        assert_eq!(calculate_number_words_needed(Some(8), None, 0, 4000), 8);
+        assert_eq!(calculate_number_words_needed(Some(8), None, 0, 4000), Ok(8));
     }
 
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_strength_count()
     {
         // 100 / 13 == a little over 7, so need 8 words to satisfy
-        assert_eq!(calculate_number_words_needed(None, None, 1, 8192), 8);
+        assert_eq!(calculate_number_words_needed(None, None, 1, 8192), Ok(8));
         // 120 / 13 == a little over 9, so need 10 words to satisfy
-        assert_eq!(calculate_number_words_needed(None, None, 2, 8192), 10);
+        assert_eq!(calculate_number_words_needed(None, None, 2, 8192), Ok(10));
     }
 
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_desired_minimum_entropy()
      {
-        assert_eq!(calculate_number_words_needed(None, Some(102), 0, 8192), 8);
-        assert_eq!(calculate_number_words_needed(None, Some(106), 0, 8192), 9);
+        assert_eq!(
+            calculate_number_words_needed(None, Some(102), 0, 8192),
+            Ok(8)
+        );
+        assert_eq!(
+            calculate_number_words_needed(None, Some(106), 0, 8192),
+            Ok(9)
+        );
     }
 
     #[test]
@@ -36,7 +42,71 @@ mod minimum_entropy_tests {
         // this hypothetical list
         assert_eq!(
             convert_minimum_entropy_to_number_of_words(desired_minimum_entropy, list_length),
-            4
+            Ok(4)
+        );
+    }
+
+    #[test]
+    fn can_size_passphrases_from_weighted_lists_by_min_entropy() {
+        // Most likely word on this list has a 1 in 4 chance, so each word is only counted on
+        // for 2 bits, even though the list's Shannon entropy is higher
+        let weights = [4.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0];
+        let bits_per_word = phraze::list_info::min_entropy_per_word(&weights);
+        assert_eq!(bits_per_word, 2.0);
+        assert!(phraze::list_info::shannon_entropy_per_word(&weights) > 2.0);
+        assert_eq!(
+            calculate_number_words_needed_from_bits_per_word(None, Some(51), 0, bits_per_word),
+            Ok(26)
+        );
+        assert_eq!(
+            calculate_number_words_needed_from_bits_per_word(Some(3), Some(51), 0, bits_per_word),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn can_size_passphrases_made_from_templates_slot_by_slot() {
+        // Slots of 16, 8 and 4 words add 4, 3 and 2 bits, and the template repeats after that
+        let slot_sizes = [16, 8, 4];
+        assert_eq!(entropy_of_slots(&slot_sizes, 3), 9.0);
+        assert_eq!(entropy_of_slots(&slot_sizes, 5), 16.0);
+        assert_eq!(
+            calculate_number_words_needed_per_slot(None, Some(16), 0, &slot_sizes),
+            5
+        );
+        assert_eq!(
+            calculate_number_words_needed_per_slot(None, Some(17), 0, &slot_sizes),
+            6
+        );
+        assert_eq!(
+            calculate_number_words_needed_per_slot(Some(2), None, 0, &slot_sizes),
+            2
+        );
+        // 80 bits by default: 8 full templates give 72 bits, then 4 + 3 + 2 more
+        assert_eq!(
+            calculate_number_words_needed_per_slot(None, None, 0, &slot_sizes),
+            27
+        );
+    }
+
+    #[test]
+    fn can_size_random_passwords_by_treating_alphabet_as_a_list() {
+        use phraze::alphabets::Alphabet;
+        // 62 characters add about 5.95 bits each, so 80 bits takes 14 of them
+        let alnum_length = Alphabet::Alnum.chars().len();
+        assert_eq!(
+            calculate_number_words_needed(None, None, 0, alnum_length),
+            Ok(14)
+        );
+        // 94 printable characters add about 6.55 bits each
+        let printable_length = Alphabet::Printable.chars().len();
+        assert_eq!(
+            calculate_number_words_needed(None, None, 0, printable_length),
+            Ok(13)
+        );
+        assert_eq!(
+            calculate_number_words_needed(None, None, 1, printable_length),
+            Ok(16)
         );
     }
 }
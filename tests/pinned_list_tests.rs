@@ -0,0 +1,76 @@
+#![cfg(feature = "cli")]
+mod pinned_list_tests {
+    use std::fs;
+    use std::io::Write;
+    use std::path::Path;
+    use std::process::{Command, Output, Stdio};
+
+    /// Run the phraze binary with the given arguments and pins directory, feeding it the given
+    /// bytes on stdin
+    fn run_phraze(args: &[&str], config_home: &Path, stdin: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_phraze"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", config_home)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("phraze binary runs");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin)
+            .expect("can write to phraze's stdin");
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn subcommands_refuse_a_list_that_does_not_match_its_pin() {
+        let base = std::env::temp_dir().join(format!(
+            "phraze-pinned-list-subcommand-test-{}",
+            std::process::id()
+        ));
+        let config_home = base.join("config");
+        fs::create_dir_all(config_home.join("phraze")).unwrap();
+        let list = base.join("team.txt");
+        fs::write(&list, "apple\nbanana\ncherry\n").unwrap();
+        let list_path = list.to_str().unwrap();
+        let pins_file = config_home.join("phraze").join("pinned-lists");
+
+        let subcommands: [(&[&str], &[u8]); 6] = [
+            (&["encode", "-c", list_path], b"\x01\x02"),
+            (&["decode", "-c", list_path], b"banana-cherry\n"),
+            (&["split", "-c", list_path], b"applebanana\n"),
+            (&["verify", "-c", list_path], b"apple-banana\n"),
+            (&["recover", "-c", list_path, "aple-banana"], b""),
+            (&["check", "-c", list_path], b"applebanana\n"),
+        ];
+
+        // Pinned to the list's actual hash, every subcommand uses the list
+        let sha256 = "6883ada0490e1bd845b6032e95119d522212f98b840eef466ebb09f4a9eb7a03";
+        fs::write(&pins_file, format!("{}  {}\n", sha256, list_path)).unwrap();
+        for (args, stdin) in subcommands {
+            let output = run_phraze(args, &config_home, stdin);
+            assert!(
+                !String::from_utf8_lossy(&output.stderr).contains("pinned"),
+                "phraze {} didn't accept a list matching its pin",
+                args.join(" ")
+            );
+        }
+
+        // Pinned to some other hash, every subcommand refuses it
+        let other_sha256 = "0".repeat(64);
+        fs::write(&pins_file, format!("{}  {}\n", other_sha256, list_path)).unwrap();
+        for (args, stdin) in subcommands {
+            let output = run_phraze(args, &config_home, stdin);
+            assert!(
+                !output.status.success(),
+                "phraze {} used a list that doesn't match its pin",
+                args.join(" ")
+            );
+            assert!(String::from_utf8_lossy(&output.stderr).contains("pinned"));
+        }
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
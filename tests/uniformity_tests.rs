@@ -0,0 +1,309 @@
+mod uniformity_tests {
+    use phraze::list_choice::ListChoice;
+    use phraze::pseudo_words::PseudoWords;
+    use phraze::separators::{CHARSET, make_separator};
+    use phraze::*;
+    use rand::SeedableRng;
+    use rand::distr::weighted::WeightedIndex;
+    use rand::rngs::StdRng;
+    use std::collections::HashMap;
+
+    // These tests draw lots of samples from Phraze's random choices, using a seeded RNG so that
+    // results can be reproduced, and use chi-squared tests to check that the samples fit the
+    // distribution we expect: every word equally likely, no matter where it falls in the
+    // passphrase or what word came before it, and separators picked the way Phraze says they are.
+    //
+    // The quick versions run with `cargo test`. For a longer run with more samples and more
+    // seeds, run `cargo test --release --test uniformity_tests -- --ignored`.
+
+    /// z-score for a significance level of 0.001
+    const Z_SCORE: f64 = 3.090;
+
+    /// Sample sizes are chosen so that each possible outcome is expected this many times
+    const EXPECTED_PER_OUTCOME: usize = 10;
+    const EXPECTED_PER_OUTCOME_LONG_RUN: usize = 200;
+    const SEEDS_FOR_LONG_RUN: [u64; 5] = [1, 2, 3, 4, 5];
+
+    /// Words per passphrase, when looking at where in a passphrase a word falls
+    const WORDS_PER_PASSPHRASE: usize = 4;
+
+    /// Words are grouped into this many buckets (by index, modulo the number of buckets) when
+    /// there are too few samples to test every word on its own
+    const BUCKETS: usize = 32;
+
+    fn chi_squared_statistic(observed: &[usize], expected: &[f64]) -> f64 {
+        observed
+            .iter()
+            .zip(expected)
+            .map(|(observed, expected)| (*observed as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    /// Wilson-Hilferty approximation of the chi-squared critical value. It's accurate enough for
+    /// the large numbers of degrees of freedom we use here.
+    fn critical_value(degrees_of_freedom: usize) -> f64 {
+        let k = degrees_of_freedom as f64;
+        let a = 2.0 / (9.0 * k);
+        k * (1.0 - a + Z_SCORE * a.sqrt()).powi(3)
+    }
+
+    /// Check that the observed counts fit the given probabilities of each outcome
+    fn assert_fits_distribution(observed: &[usize], probabilities: &[f64], description: &str) {
+        let total: usize = observed.iter().sum();
+        let expected: Vec<f64> = probabilities.iter().map(|p| p * total as f64).collect();
+        let statistic = chi_squared_statistic(observed, &expected);
+        let critical_value = critical_value(observed.len() - 1);
+        assert!(
+            statistic < critical_value,
+            "{}: chi-squared statistic of {:.1} is over the critical value of {:.1}",
+            description,
+            statistic,
+            critical_value
+        );
+    }
+
+    fn uniform(outcomes: usize) -> Vec<f64> {
+        vec![1.0 / outcomes as f64; outcomes]
+    }
+
+    /// Share of the list's words that fall in each bucket
+    fn bucket_probabilities(list_length: usize) -> Vec<f64> {
+        (0..BUCKETS)
+            .map(|bucket| (list_length - bucket).div_ceil(BUCKETS) as f64 / list_length as f64)
+            .collect()
+    }
+
+    /// Generate passphrases from the given list, then check that:
+    /// 1. every word on the list is equally likely,
+    /// 2. at every position in the passphrase, and
+    /// 3. no matter what word came before it.
+    fn check_word_distribution(list_choice: ListChoice, expected_per_outcome: usize, seed: u64) {
+        let list = fetch_list(list_choice);
+        let indexes: HashMap<&str, usize> = list
+            .iter()
+            .enumerate()
+            .map(|(index, word)| (*word, index))
+            .collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let number_of_passphrases =
+            (expected_per_outcome * list.len()).div_ceil(WORDS_PER_PASSPHRASE);
+
+        let mut word_counts = vec![0; list.len()];
+        let mut bucket_counts_by_position = vec![vec![0; BUCKETS]; WORDS_PER_PASSPHRASE];
+        let mut pair_counts = vec![0; BUCKETS * BUCKETS];
+        for _ in 0..number_of_passphrases {
+            let passphrase = generate_a_passphrase_with_rng(
+                &mut rng,
+                WORDS_PER_PASSPHRASE,
+                " ",
+                false,
+                false,
+                None,
+                list,
+            )
+            .unwrap();
+            let word_indexes: Vec<usize> = passphrase.split(' ').map(|w| indexes[w]).collect();
+            for (position, index) in word_indexes.iter().enumerate() {
+                word_counts[*index] += 1;
+                bucket_counts_by_position[position][index % BUCKETS] += 1;
+            }
+            for pair in word_indexes.windows(2) {
+                pair_counts[(pair[0] % BUCKETS) * BUCKETS + pair[1] % BUCKETS] += 1;
+            }
+        }
+
+        assert_fits_distribution(
+            &word_counts,
+            &uniform(list.len()),
+            &format!("{} word frequencies", list_choice),
+        );
+        let buckets = bucket_probabilities(list.len());
+        for (position, bucket_counts) in bucket_counts_by_position.iter().enumerate() {
+            assert_fits_distribution(
+                bucket_counts,
+                &buckets,
+                &format!("{} words at position {}", list_choice, position + 1),
+            );
+        }
+        // If each word is independent of the one before it, the chance of seeing a given pair of
+        // buckets is just the product of the chance of each
+        let pair_probabilities: Vec<f64> = buckets
+            .iter()
+            .flat_map(|first| buckets.iter().map(move |second| first * second))
+            .collect();
+        assert_fits_distribution(
+            &pair_counts,
+            &pair_probabilities,
+            &format!("{} pairs of neighboring words", list_choice),
+        );
+    }
+
+    /// Every character a random separator can be, along with how likely Phraze should be to pick
+    /// it for the given separator setting. `_b` picks numbers and symbols equally often, and then a
+    /// character of that type, so each digit is more likely than each symbol.
+    fn separator_probabilities(separator: &str) -> Vec<(char, f64)> {
+        let digits = ('0'..='9').collect::<Vec<char>>();
+        let symbols: Vec<char> = CHARSET.iter().map(|c| *c as char).collect();
+        let with_probability =
+            |chars: &[char], p: f64| chars.iter().map(|c| (*c, p)).collect::<Vec<_>>();
+        match separator {
+            "_n" => with_probability(&digits, 1.0 / digits.len() as f64),
+            "_s" => with_probability(&symbols, 1.0 / symbols.len() as f64),
+            "_b" => [
+                with_probability(&digits, 0.5 / digits.len() as f64),
+                with_probability(&symbols, 0.5 / symbols.len() as f64),
+            ]
+            .concat(),
+            _ => panic!("Not a random separator setting: {}", separator),
+        }
+    }
+
+    fn check_separator_distribution(separator: &str, expected_per_outcome: usize, seed: u64) {
+        let probabilities = separator_probabilities(separator);
+        let positions: HashMap<char, usize> = probabilities
+            .iter()
+            .enumerate()
+            .map(|(position, (c, _))| (*c, position))
+            .collect();
+        // Make sure even the least likely character is expected `expected_per_outcome` times
+        let smallest_probability = probabilities.iter().map(|(_, p)| *p).fold(1.0, f64::min);
+        let samples = (expected_per_outcome as f64 / smallest_probability).ceil() as usize;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut counts = vec![0; probabilities.len()];
+        for _ in 0..samples {
+            let made = make_separator(&mut rng, separator);
+            let mut chars = made.chars();
+            let c = chars.next().unwrap();
+            assert!(
+                chars.next().is_none(),
+                "Separator '{}' isn't one character",
+                made
+            );
+            counts[positions[&c]] += 1;
+        }
+        assert_fits_distribution(
+            &counts,
+            &probabilities.iter().map(|(_, p)| *p).collect::<Vec<f64>>(),
+            &format!("'{}' separator characters", separator),
+        );
+    }
+
+    #[test]
+    fn every_word_on_every_list_is_equally_likely() {
+        for list_choice in ListChoice::ALL {
+            check_word_distribution(*list_choice, EXPECTED_PER_OUTCOME, 8192);
+        }
+    }
+
+    #[test]
+    fn words_from_weighted_lists_are_picked_in_proportion_to_their_weights() {
+        let list = ["apple", "banana", "cherry", "date"];
+        let weights = [4.0, 2.0, 1.0, 1.0];
+        let weighted_index = WeightedIndex::new(weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(8192);
+        let mut counts = vec![0; list.len()];
+        for _ in 0..1_000 {
+            let passphrase = generate_a_weighted_passphrase_with_rng(
+                &mut rng,
+                &weighted_index,
+                WORDS_PER_PASSPHRASE,
+                " ",
+                false,
+                false,
+                None,
+                &list,
+            )
+            .unwrap();
+            for word in passphrase.split(' ') {
+                counts[list.iter().position(|w| *w == word).unwrap()] += 1;
+            }
+        }
+        let probabilities: Vec<f64> = weights.iter().map(|weight| weight / 8.0).collect();
+        assert_fits_distribution(&counts, &probabilities, "weighted word frequencies");
+    }
+
+    #[test]
+    fn every_letter_of_pseudo_words_is_equally_likely() {
+        let pseudo_words = PseudoWords::new("cvc".parse().unwrap());
+        let mut rng = StdRng::seed_from_u64(8192);
+        let mut counts_by_spot: Vec<Vec<usize>> = pseudo_words
+            .pattern
+            .letters
+            .iter()
+            .map(|letter| vec![0; pseudo_words.letters_for(*letter).len()])
+            .collect();
+        for _ in 0..200 {
+            let passphrase = generate_a_pseudo_word_passphrase_with_rng(
+                &mut rng,
+                &pseudo_words,
+                WORDS_PER_PASSPHRASE,
+                " ",
+                false,
+                None,
+            )
+            .unwrap();
+            for pseudo_word in passphrase.split(' ') {
+                assert_eq!(pseudo_word.len(), pseudo_words.length());
+                for (spot, c) in pseudo_word.chars().enumerate() {
+                    let letters = pseudo_words.letters_for(pseudo_words.pattern.letters[spot]);
+                    counts_by_spot[spot][letters.iter().position(|l| *l == c).unwrap()] += 1;
+                }
+            }
+        }
+        for (spot, counts) in counts_by_spot.iter().enumerate() {
+            assert_fits_distribution(
+                counts,
+                &uniform(counts.len()),
+                &format!("pseudo-word letters at spot {}", spot + 1),
+            );
+        }
+    }
+
+    #[test]
+    fn random_separators_are_picked_as_expected() {
+        for separator in ["_n", "_s", "_b"] {
+            check_separator_distribution(separator, EXPECTED_PER_OUTCOME * 10, 8192);
+        }
+    }
+
+    #[test]
+    fn numbers_and_symbols_are_equally_likely_with_both_as_separators() {
+        let mut rng = StdRng::seed_from_u64(8192);
+        let mut counts = [0; 2];
+        for _ in 0..10_000 {
+            let made = make_separator(&mut rng, "_b");
+            let is_digit = made.chars().all(|c| c.is_ascii_digit());
+            counts[if is_digit { 0 } else { 1 }] += 1;
+        }
+        assert_fits_distribution(&counts, &uniform(2), "'_b' separator types");
+    }
+
+    #[test]
+    fn fixed_separators_are_used_as_given() {
+        let mut rng = StdRng::seed_from_u64(8192);
+        for separator in ["-", "", " ", "_"] {
+            assert_eq!(make_separator(&mut rng, separator), separator);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn every_word_on_every_list_is_equally_likely_long_run() {
+        for seed in SEEDS_FOR_LONG_RUN {
+            for list_choice in ListChoice::ALL {
+                check_word_distribution(*list_choice, EXPECTED_PER_OUTCOME_LONG_RUN, seed);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn random_separators_are_picked_as_expected_long_run() {
+        for seed in SEEDS_FOR_LONG_RUN {
+            for separator in ["_n", "_s", "_b"] {
+                check_separator_distribution(separator, EXPECTED_PER_OUTCOME_LONG_RUN * 10, seed);
+            }
+        }
+    }
+}
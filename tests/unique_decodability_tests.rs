@@ -0,0 +1,54 @@
+mod unique_decodability_tests {
+    use phraze::decompose::{WordLookup, decompose};
+    use phraze::list_choice::ListChoice;
+    use phraze::*;
+    use rand::SeedableRng;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+
+    /// Make a bunch of passphrases from the given list, with the given separator, and make sure
+    /// each one can only be broken back down into the words it was made from, one way.
+    fn assert_passphrases_decompose_back_into_words_one_way(
+        list_choice: ListChoice,
+        separator: &str,
+    ) {
+        let list = fetch_list(list_choice);
+        let lookup = WordLookup::new(list);
+        let mut rng = StdRng::seed_from_u64(list.len() as u64);
+        for _ in 0..200 {
+            let words: Vec<&str> = (0..6).map(|_| *list.choose(&mut rng).unwrap()).collect();
+            let passphrase = words.join(separator);
+            let decompositions = decompose(&passphrase, &lookup, 2);
+            assert_eq!(
+                decompositions.len(),
+                1,
+                "{} from the {} list could be broken down {} ways",
+                passphrase,
+                list_choice,
+                decompositions.len()
+            );
+            let decomposed_words: Vec<&str> = decompositions[0]
+                .word_indexes
+                .iter()
+                .map(|index| list[*index])
+                .collect();
+            assert_eq!(decomposed_words, words);
+        }
+    }
+
+    #[test]
+    fn can_decompose_passphrases_with_no_separator_back_into_words_one_way() {
+        for list_choice in ListChoice::ALL {
+            assert_passphrases_decompose_back_into_words_one_way(*list_choice, "");
+        }
+    }
+
+    #[test]
+    fn can_decompose_passphrases_with_separators_back_into_words_one_way() {
+        for list_choice in ListChoice::ALL {
+            for separator in ["-", " ", "_"] {
+                assert_passphrases_decompose_back_into_words_one_way(*list_choice, separator);
+            }
+        }
+    }
+}